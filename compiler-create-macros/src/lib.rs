@@ -0,0 +1,254 @@
+//! The `lexer!` macro, building a lexer's DFA while compiling.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use compiler_create::lexica::{
+    precompile::{precompile, SpecError, TokenSpec},
+    tokens::TokenUses
+};
+
+/// Declares a lexer type from a token spec, checked and turned into a minimized DFA at
+/// compile time:
+///
+/// ```ignore
+/// lexer! {
+///     pub struct MainLexer for Vars;
+///     If: Default = "if" over Id;
+///     Id: GetLexeme = r"[a-zA-Z][a-zA-Z0-9]*";
+///     Spaces: IgnoreThis = r"[ \n\t]+";
+/// }
+/// ```
+///
+/// Each line is a variant of the token enum, its `TokenUses` and its regex. Invalid regexes,
+/// tokens never produced and conflicts are compile errors; `over` lists the tokens one is
+/// meant to win against. `MainLexer::new(path)` gives a `LexicalAnalysis` over the file and
+/// `MainLexer::dfa()` the `TokensDFA`.
+#[proc_macro]
+pub fn lexer(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(errors) => errors.into_iter().map(|(span, message)| compile_error(span, &message)).collect(),
+    }
+}
+
+type Errors = Vec<(Span, String)>;
+
+/// A declaration, with where to point errors about it.
+struct Declaration {
+    spec: TokenSpec,
+    name: Span,
+    pattern: Span,
+    /// The names listed after `over`, in order.
+    over: Vec<Span>,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Errors> {
+    let mut tokens = Tokens { trees: input.into_iter().collect(), position: 0 };
+
+    let mut visibility = String::new();
+    while !tokens.is_ident("struct") {
+        let Some(tree) = tokens.next() else {
+            return Err(vec![(Span::call_site(), "expected `struct Name for TokenType;`".to_string())]);
+        };
+        visibility.push_str(&tree.to_string());
+        visibility.push(' ');
+    }
+    tokens.next();
+    let (lexer, _) = tokens.ident()?;
+    if !tokens.is_ident("for") {
+        return Err(vec![(tokens.span(), "expected `for TokenType`".to_string())]);
+    }
+    tokens.next();
+    let mut token_type = String::new();
+    while !tokens.is_punct(';') {
+        let Some(tree) = tokens.next() else {
+            return Err(vec![(tokens.span(), "expected `;`".to_string())]);
+        };
+        token_type.push_str(&tree.to_string());
+    }
+    tokens.next();
+
+    let mut declarations = Vec::new();
+    while tokens.peek().is_some() {
+        declarations.push(declaration(&mut tokens)?);
+    }
+    if declarations.is_empty() {
+        return Err(vec![(Span::call_site(), "a lexer needs at least one token".to_string())]);
+    }
+
+    let specs: Vec<TokenSpec> = declarations.iter().map(|declaration| declaration.spec.clone()).collect();
+    let precompiled = precompile(&specs).map_err(|errors| errors.into_iter()
+        .map(|error| match &error {
+            SpecError::Pattern { token, .. } => (declarations[*token].pattern, error.to_string()),
+            SpecError::UnknownOver { token, name } => {
+                let declaration = &declarations[*token];
+                let index = declaration.spec.over.iter().position(|other| other == name).unwrap();
+                (declaration.over[index], error.to_string())
+            },
+            SpecError::Conflict { token, .. } | SpecError::Shadowed { token, .. } => (declarations[*token].name, error.to_string()),
+        })
+        .collect::<Errors>())?;
+
+    let variants: Vec<String> = precompiled.names.iter().map(|name| format!("{}::{}", token_type, name)).collect();
+    let output = format!(
+        "{visibility}struct {lexer};
+
+        impl {lexer} {{
+            pub const TOKENS: &'static [{token_type}] = &[{variants}];
+            const DFA: &'static [u8] = {dfa};
+
+            pub fn dfa() -> ::compiler_create::lexica::automata::TokensDFA<{token_type}> {{
+                ::compiler_create::lexica::automata::TokensDFA::from_bytes(Self::DFA, Self::TOKENS)
+                    .expect(\"the DFA was saved by `lexer!`\")
+            }}
+
+            pub fn new(filepath: &str) -> ::compiler_create::lexica::analysis::LexicalAnalysis<{token_type}> {{
+                ::compiler_create::lexica::analysis::LexicalAnalysis::from_dfa(Self::dfa(), filepath)
+            }}
+        }}",
+        variants = variants.join(", "),
+        dfa = Literal::byte_string(&precompiled.dfa),
+    );
+    Ok(output.parse().unwrap())
+}
+
+/// `Name: Uses = "regex" over Other, ...;`
+fn declaration(tokens: &mut Tokens) -> Result<Declaration, Errors> {
+    let (name, name_span) = tokens.ident()?;
+    tokens.punct(':')?;
+    let (uses, uses_span) = tokens.ident()?;
+    let uses = match uses.as_str() {
+        "Default" => TokenUses::Default,
+        "GetLexeme" => TokenUses::GetLexeme,
+        "IgnoreThis" => TokenUses::IgnoreThis,
+        "InitBlockComment" => TokenUses::InitBlockComment,
+        "EndBlockComment" => TokenUses::EndBlockComment,
+        "InitInlineComment" => TokenUses::InitInlineComment,
+        _ => return Err(vec![(uses_span, format!("unknown token use `{}`", uses))]),
+    };
+    tokens.punct('=')?;
+    let Some(TokenTree::Literal(literal)) = tokens.next() else {
+        return Err(vec![(tokens.span(), "expected the regex as a string literal".to_string())]);
+    };
+    let Some(pattern) = unquote(&literal.to_string()) else {
+        return Err(vec![(literal.span(), "expected the regex as a string literal".to_string())]);
+    };
+    let mut spec = TokenSpec::new(&name, uses, &pattern);
+    let mut over = Vec::new();
+    if tokens.is_ident("over") {
+        tokens.next();
+        loop {
+            let (name, span) = tokens.ident()?;
+            spec.over.push(name);
+            over.push(span);
+            if !tokens.is_punct(',') {
+                break;
+            }
+            tokens.next();
+        }
+    }
+    if tokens.peek().is_some() {
+        tokens.punct(';')?;
+    }
+    Ok(Declaration { spec, name: name_span, pattern: literal.span(), over })
+}
+
+struct Tokens {
+    trees: Vec<TokenTree>,
+    position: usize,
+}
+
+impl Tokens {
+    fn peek(&self) -> Option<&TokenTree> {
+        self.trees.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let tree = self.peek().cloned();
+        self.position += 1;
+        tree
+    }
+
+    /// Where the next token is, or the whole macro call at the end.
+    fn span(&self) -> Span {
+        self.peek().map_or(Span::call_site(), |tree| tree.span())
+    }
+
+    fn is_ident(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == word)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+    }
+
+    fn ident(&mut self) -> Result<(String, Span), Errors> {
+        match self.peek() {
+            Some(TokenTree::Ident(ident)) => {
+                let ident = (ident.to_string(), ident.span());
+                self.position += 1;
+                Ok(ident)
+            },
+            _ => Err(vec![(self.span(), "expected an identifier".to_string())]),
+        }
+    }
+
+    fn punct(&mut self, c: char) -> Result<(), Errors> {
+        if !self.is_punct(c) {
+            return Err(vec![(self.span(), format!("expected `{}`", c))]);
+        }
+        self.position += 1;
+        Ok(())
+    }
+}
+
+/// The value of a string or raw string literal, as written in the source.
+fn unquote(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw.get(hashes + 1..raw.len().checked_sub(hashes + 1)?).map(str::to_string);
+    }
+    let mut chars = literal.strip_prefix('"')?.strip_suffix('"')?.chars().peekable();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                value.push(u8::from_str_radix(&hex, 16).ok()? as char);
+            },
+            'u' => {
+                let hex: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            },
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
+
+/// `compile_error!("message");` pointing at `span`.
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    let trees = [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::from(TokenTree::Literal(message)))),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+    ];
+    trees.into_iter()
+        .map(|mut tree| {
+            tree.set_span(span);
+            tree
+        })
+        .collect()
+}
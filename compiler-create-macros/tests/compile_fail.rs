@@ -0,0 +1,6 @@
+/// Spec errors are `compile_error!`s pointing at the declaration at fault.
+#[test]
+fn spec_errors_point_at_their_declaration() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,115 @@
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    regex::Regex,
+    tokens::TokenUses
+};
+use compiler_create_macros::lexer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Vars {
+    If, Else, While, Let, Set, Id,
+    ConstInt, ConstFloat, ConstChar,
+    Add, Sub, Mul, Div,
+    LessThan, GreatThan, LessEquals, GreatEquals, Equals, NotEquals,
+    RightBrace, LeftBrace, RightParentheses, LeftParentheses,
+    Comma, Semicolon,
+    Ws, LineComment, InitComment, EndComment,
+}
+
+/// Declares `MainLexer` with `lexer!` and, from the same declarations, `main_tokens` for
+/// building the reference `LexicalAnalysis`.
+macro_rules! main_lexer {
+    ($($name:ident: $uses:ident = $pattern:tt $(over $($over:ident),+)?;)*) => {
+        lexer! {
+            struct MainLexer for Vars;
+            $($name: $uses = $pattern $(over $($over),+)?;)*
+        }
+
+        fn main_tokens() -> Vec<(Vars, TokenUses, Regex)> {
+            vec![$((Vars::$name, TokenUses::$uses, Regex::parse($pattern).unwrap())),*]
+        }
+    };
+}
+
+// The token specification of `src/main.rs`.
+main_lexer! {
+    If: Default = "if" over Id;
+    Else: Default = "else" over Id;
+    While: Default = "while" over Id;
+    Let: Default = "let" over Id;
+    Set: Default = "=";
+
+    Id: GetLexeme = "[a-zA-Z][a-zA-Z0-9]*";
+
+    ConstInt: GetLexeme = "[0-9]+";
+    ConstFloat: GetLexeme = r"[0-9]+\.[0-9]+";
+    ConstChar: GetLexeme = r"'([^\\'\n]|\\[\x00-\xFF])'";
+
+    Add: Default = r"\+";
+    Sub: Default = "-";
+    Mul: Default = r"\*";
+    Div: Default = r"\/";
+
+    LessThan: Default = ">";
+    GreatThan: Default = "<";
+    LessEquals: Default = ">=";
+    GreatEquals: Default = "<=";
+    Equals: Default = "==";
+    NotEquals: Default = "!=";
+
+    RightBrace: Default = r"\}";
+    LeftBrace: Default = r"\{";
+    RightParentheses: Default = r"\)";
+    LeftParentheses: Default = r"\(";
+
+    Comma: Default = ",";
+    Semicolon: Default = ";";
+
+    Ws: IgnoreThis = "[\r\n\t \0]";
+    LineComment: InitInlineComment = r"\/\/";
+    InitComment: InitBlockComment = r"\/\*";
+    EndComment: EndBlockComment = r"\*\/";
+}
+
+lexer! {
+    pub(crate) struct Words for Vars;
+    Id: GetLexeme = r#"[a-z]+"#;
+    Ws: IgnoreThis = " |\n";
+}
+
+fn lex(lexer: LexicalAnalysis<Vars>) -> Vec<(Vars, Option<String>)> {
+    lexer.map(|token| (token.t_type, token.t_name)).collect()
+}
+
+#[test]
+fn macro_lexer_matches_lexical_analysis() {
+    let source = "let x1 = 3.25; // if\nif (x1 >= 10) { x1 = 'a' + '\\n'; }\n/* while 1 */ while x1 != 0 { x1 = x1 - 1; }\nf(x1 * 2, x1 / 2) < 1 == x1 > 0;";
+    let path = std::env::temp_dir().join("compiler_create_macros_main.txt");
+    fs::write(&path, source).unwrap();
+    let path = path.to_str().unwrap();
+
+    let expected = lex(LexicalAnalysis::new(main_tokens(), path));
+    let lexed = lex(MainLexer::new(path));
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(lexed, expected);
+    assert_eq!(lexed[0], (Vars::Let, None));
+    assert_eq!(lexed.iter().filter(|(name, _)| *name == Vars::While).count(), 1);
+    assert!(lexed.contains(&(Vars::ConstChar, Some("'\\n'".to_string()))));
+    for name in [Vars::Mul, Vars::Div, Vars::LessThan, Vars::GreatThan, Vars::Equals, Vars::Comma] {
+        assert!(lexed.contains(&(name, None)), "{:?}", name);
+    }
+}
+
+#[test]
+fn macro_lexer_exposes_its_tokens_and_dfa() {
+    assert_eq!(MainLexer::TOKENS.len(), 29);
+    assert_eq!(Words::TOKENS, &[Vars::Id, Vars::Ws]);
+
+    let dfa = Words::dfa();
+    assert_eq!(dfa.test_string("lexer".to_string()), Some(Vars::Id));
+    assert_eq!(dfa.test_string("Lexer".to_string()), None);
+    assert_eq!(dfa.states, 3);
+}
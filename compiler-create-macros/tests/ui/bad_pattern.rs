@@ -0,0 +1,12 @@
+use compiler_create_macros::lexer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind { Int, Open }
+
+lexer! {
+    struct Broken for Kind;
+    Int: GetLexeme = "[0-9]+";
+    Open: Default = "(";
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use compiler_create_macros::lexer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind { If, Id }
+
+lexer! {
+    struct Keywords for Kind;
+    Id: GetLexeme = "[a-z]+" over If;
+    If: Default = "if";
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use compiler_create_macros::lexer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind { If, Id }
+
+lexer! {
+    struct Keywords for Kind;
+    If: Default = "if" over Id, Ident;
+    Id: GetLexeme = "[a-z]+";
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use compiler_create_macros::lexer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind { Id }
+
+lexer! {
+    struct Words for Kind;
+    Id: Lexeme = "[a-z]+";
+}
+
+fn main() {}
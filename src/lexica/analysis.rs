@@ -2,40 +2,136 @@ use std::{fs::File, io::Read};
 use std::hash::Hash;
 
 use crate::lexica::regex::Regex;
-use crate::lexica::automata::{TokensDFA, ERROR_STATE, INIT_STATE};
+use crate::lexica::automata::{LexemeRules, State, TokensDFA, ERROR_STATE, INIT_STATE};
+use crate::lexica::compressed::CompressedDFA;
+use crate::lexica::pike::PikeVM;
 use crate::lexica::tokens::{Token, TokenUses};
 
 
 const MAX_SIZE_LEXEME: usize = 512;
+
+/// How `LexicalAnalysis` recognizes tokens, all follow maximal munch and give ties to
+/// the first declared token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Builds the minimized `TokensDFA` up front, one table lookup per byte.
+    #[default]
+    TokensDFA,
+    /// Simulates the `TokensNFA`, for token sets whose DFA would be too large to build.
+    PikeVM,
+    /// The minimized `TokensDFA` with packed rows, smaller tables for a few more lookups per byte.
+    CompressedDFA,
+}
+
+enum Engine<T> where T: Eq + Copy + Hash {
+    TokensDFA(TokensDFA<T>),
+    PikeVM(PikeVM<T>),
+    CompressedDFA(CompressedDFA<T>),
+}
+
+impl<T> Engine<T> where T: Eq + Copy + Hash {
+    fn start(&self) -> Vec<State> {
+        match self {
+            Engine::TokensDFA(_) | Engine::CompressedDFA(_) => vec![INIT_STATE],
+            Engine::PikeVM(vm) => vm.start(),
+        }
+    }
+
+    /// Moves `threads` over `byte`, returns false when no token can match anymore.
+    fn step(&self, threads: &mut Vec<State>, byte: u8) -> bool {
+        match self {
+            Engine::TokensDFA(dfa) => {
+                threads[0] = dfa.next(threads[0], byte);
+                threads[0] != ERROR_STATE
+            },
+            Engine::PikeVM(vm) => {
+                *threads = vm.step(threads, byte);
+                !threads.is_empty()
+            },
+            Engine::CompressedDFA(dfa) => {
+                threads[0] = dfa.next(threads[0], byte);
+                threads[0] != ERROR_STATE
+            },
+        }
+    }
+
+    /// The accepted token, its uses and the key of its declaration's lexeme rules.
+    fn accepted(&self, threads: &[State]) -> Option<(T, TokenUses, Option<usize>)> {
+        match self {
+            Engine::TokensDFA(dfa) => match dfa.finals[threads[0] as usize] {
+                (Some(token_type), mask) => Some((token_type, mask, dfa.rules[threads[0] as usize])),
+                (None, _) => None,
+            },
+            Engine::PikeVM(vm) => vm.accepted(threads),
+            Engine::CompressedDFA(dfa) => match dfa.finals[threads[0] as usize] {
+                (Some(token_type), mask) => Some((token_type, mask, dfa.rules[threads[0] as usize])),
+                (None, _) => None,
+            },
+        }
+    }
+
+    fn lexeme_rules(&self) -> &LexemeRules<T> {
+        match self {
+            Engine::TokensDFA(dfa) => &dfa.lexeme_rules,
+            Engine::PikeVM(vm) => &vm.lexeme_rules,
+            Engine::CompressedDFA(dfa) => &dfa.lexeme_rules,
+        }
+    }
+}
+
+/// Position of the lexer in the input, restored to give back bytes read past a lexeme.
+#[derive(Clone, Copy)]
+struct Cursor {
+    row: u16,
+    col: u16,
+    next: usize,
+    c: u8,
+    eof: bool,
+    consumed: usize,
+}
+
 pub struct LexicalAnalysis<T> where T: Eq + Copy + Hash {
     pub row: u16, 
     pub col: u16,
     init: usize,
     next: usize,
     c: u8,
+    /// Set once `c` is past the last byte of the file, `c` is then meaningless.
+    eof: bool,
+    consumed: usize,
     
     buffer: [u8; (MAX_SIZE_LEXEME as usize) * 2],
     bytes_loaded: usize,
     buffer_loaded: bool,
-    last_buffer: bool,
 
 
     file: File,
-    dfa_tokens: TokensDFA<T>,
+    engine: Engine<T>,
     dfa_end_comment: Option<TokensDFA<u32>>
 }
 
 impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
-    pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>, filepath: &str) -> Self {       
-        let mut dfa_end_comment = None;
-        for (_,mask, regex) in &tokens_regexs {
-            if *mask == TokenUses::EndBlockComment {
-                dfa_end_comment = Some(
-                    TokensDFA::new(vec![(0, *mask, regex.clone())]
-                ));
-            }
-        }
-        let dfa = TokensDFA::new(tokens_regexs);
+    pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>, filepath: &str) -> Self {
+        Self::with_backend(tokens_regexs, filepath, Backend::default())
+    }
+
+    pub fn with_backend(tokens_regexs: Vec<(T, TokenUses, Regex)>, filepath: &str, backend: Backend) -> Self {
+        let engine = match backend {
+            Backend::TokensDFA => Engine::TokensDFA(TokensDFA::new(tokens_regexs).minimize()),
+            Backend::PikeVM => Engine::PikeVM(PikeVM::new(tokens_regexs)),
+            Backend::CompressedDFA => Engine::CompressedDFA(CompressedDFA::new(tokens_regexs)),
+        };
+        Self::with_engine(engine, filepath)
+    }
+
+    /// Lexes with an already built DFA, e.g. one loaded with `TokensDFA::load`.
+    pub fn from_dfa(dfa: TokensDFA<T>, filepath: &str) -> Self {
+        Self::with_engine(Engine::TokensDFA(dfa), filepath)
+    }
+
+    fn with_engine(engine: Engine<T>, filepath: &str) -> Self {
+        let dfa_end_comment = engine.lexeme_rules().end_comment()
+            .map(|regex| TokensDFA::new(vec![(0, TokenUses::EndBlockComment, regex.clone())]));
 
         let mut sa = LexicalAnalysis {
             row: 0, 
@@ -43,12 +139,13 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
             init: 0,
             next: 0,
             c: 0,
+            eof: false,
+            consumed: 0,
             buffer: [0; MAX_SIZE_LEXEME * 2],
             bytes_loaded: 0,
-            last_buffer: false,
             buffer_loaded: true,
             file: File::open(filepath).unwrap(),
-            dfa_tokens: dfa,
+            engine,
             dfa_end_comment,
         };
         LexicalAnalysis::next_char(&mut sa);
@@ -56,7 +153,7 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
     }
 
     pub fn next_char(&mut self) -> u8 {
-        if (self.next - self.init + 2 * MAX_SIZE_LEXEME) % (2 * MAX_SIZE_LEXEME) > MAX_SIZE_LEXEME {
+        if (self.next + 2 * MAX_SIZE_LEXEME - self.init) % (2 * MAX_SIZE_LEXEME) > MAX_SIZE_LEXEME {
             panic!("\nError ({},{}): Lexema execede o tamanho de caracteres permitidos! ", self.row, self.col);
         }
         if self.bytes_loaded == 0 {
@@ -65,10 +162,11 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
             } else {
                 self.bytes_loaded = self.file.read(&mut self.buffer[MAX_SIZE_LEXEME..]).unwrap();
             }
-            self.last_buffer = self.bytes_loaded < MAX_SIZE_LEXEME;
             self.buffer_loaded = !self.buffer_loaded;
         }
-        self.c = if self.bytes_loaded <= 0 { 255 } else { self.buffer[self.next] };
+        self.eof = self.bytes_loaded == 0;
+        self.c = if self.eof { 0 } else { self.buffer[self.next] };
+        self.consumed = if self.eof { self.consumed } else { self.consumed + 1 };
 
         self.row = if self.c == b'\n' { self.row + 1 } else { self.row };
         self.col = if self.c == b'\n' { 1 } else { self.col + 1 };
@@ -78,27 +176,39 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
     }
 
     fn next_token(&mut self) -> Option<Token<T>> {
-        if self.bytes_loaded == 0 && self.last_buffer {
+        if self.eof {
             return None;
         }
-        let mut old_state = ERROR_STATE;
-        let mut state = INIT_STATE;
+        let mut threads = self.engine.start();
+        let mut cursors = vec![self.cursor()];
+        let mut accepted = None;
         
         loop {
-            old_state = state;
-            state = self.dfa_tokens.transitions[state as usize][self.c as usize];
-            
-            if state == ERROR_STATE {
+            if self.eof || !self.engine.step(&mut threads, self.c) {
                 break;
             }
             self.next_char();
+            cursors.push(self.cursor());
+            if let Some((token_type, mask, rule)) = self.engine.accepted(&threads) {
+                accepted = Some((token_type, mask, rule, cursors.len() - 1));
+            }
         }
 
-        if let (Some(token_type), mask) = &self.dfa_tokens.finals[old_state as usize] {
-            let t_name = Some(self.get_string_buffer());
-            self.init = self.next - 1;
+        if let Some((token_type, mask, rule, length)) = accepted {
+            // Give back the bytes read past the longest match, then the trailing context.
+            self.rewind(cursors[length]);
+            let mut lexeme = self.get_lexeme_bytes();
+            let length = self.engine.lexeme_rules().lexeme_length(rule, &lexeme);
+            if length < lexeme.len() {
+                // The context may start inside a UTF-8 character, so the bytes are cut first.
+                lexeme.truncate(length);
+                self.rewind(cursors[length]);
+            }
+            let captures = self.engine.lexeme_rules().captures(rule, &lexeme);
+            let t_name = Some(String::from_utf8_lossy(&lexeme).into_owned());
+            self.init = (self.next + 2 * MAX_SIZE_LEXEME - 1) % (2 * MAX_SIZE_LEXEME);
 
-            match *mask {
+            match mask {
                 TokenUses::IgnoreThis => { },
                 TokenUses::InitBlockComment => { 
                     self.handle_comment(); 
@@ -110,10 +220,10 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
                     panic!("Error({},{}): comment not started", self.row, self.col);
                 },
                 TokenUses::GetLexeme => { 
-                    return Some(Token { t_type: *token_type, t_name }); 
+                    return Some(Token { t_type: token_type, t_name, captures }); 
                 },
                 TokenUses::Default => { 
-                    return Some(Token { t_type: *token_type, t_name: None }); 
+                    return Some(Token { t_type: token_type, t_name: None, captures }); 
                 }
             }
             return self.next_token();
@@ -122,9 +232,24 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
         }
     }
 
+    fn cursor(&self) -> Cursor {
+        Cursor { row: self.row, col: self.col, next: self.next, c: self.c, eof: self.eof, consumed: self.consumed }
+    }
+
+    /// Moves back to `cursor`, the bytes read since then stay in the buffer to be read again.
+    fn rewind(&mut self, cursor: Cursor) {
+        self.bytes_loaded += self.consumed - cursor.consumed;
+        self.row = cursor.row;
+        self.col = cursor.col;
+        self.next = cursor.next;
+        self.c = cursor.c;
+        self.eof = cursor.eof;
+        self.consumed = cursor.consumed;
+    }
+
     fn handle_inline_comment(&mut self) {
         let init = self.init;
-        while self.c != b'\n' && self.c != 255  {  
+        while self.c != b'\n' && !self.eof {  
             self.next_char();
             self.init = self.next;
         }
@@ -151,7 +276,10 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
                 if state == final_state {
                     break 'init;
                 }
-                state = self.dfa_end_comment.as_ref().unwrap().transitions[state as usize][self.c as usize];
+                if self.eof {
+                    panic!("Error({},{}): comment not closed", self.row, self.col);
+                }
+                state = self.dfa_end_comment.as_ref().unwrap().next(state, self.c);
                 self.next_char();
                 if state == ERROR_STATE {
                     break;
@@ -160,8 +288,8 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
         }
     }
 
-    fn get_string_buffer(&self) -> String {
-        let length = (self.next - 1 - self.init + 2 * MAX_SIZE_LEXEME) % (2 * MAX_SIZE_LEXEME);
+    fn get_lexeme_bytes(&self) -> Vec<u8> {
+        let length = (self.next + 2 * MAX_SIZE_LEXEME - 1 - self.init) % (2 * MAX_SIZE_LEXEME);
         let mut j = self.init;
     
         let mut buf = Vec::new();
@@ -169,7 +297,7 @@ impl<T> LexicalAnalysis<T> where T: Eq + Copy + Hash {
             buf.push(self.buffer[j]);
             j = (j + 1) % (2 * MAX_SIZE_LEXEME);
         }
-        return std::str::from_utf8(buf.as_slice()).unwrap().to_string()
+        return buf
     }
 }
 
@@ -1,9 +1,16 @@
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::ops::{Range, RangeInclusive};
 
-use crate::lexica::{regex::Regex, tokens::TokenUses};
+use crate::lexica::{
+    derivative::dfa_from_derivatives,
+    diagnostics::{spec_warnings, LexerSpecWarning},
+    regex::Regex,
+    tokens::TokenUses
+};
 
-type State = i32;
+pub(crate) type State = i32;
+pub(crate) type Table<T> = (Vec<[State; 256]>, Vec<(T, TokenUses, State)>);
 pub const INIT_STATE: State = 0; 
 pub const ERROR_STATE: State = -1;
 
@@ -11,12 +18,15 @@ pub const ERROR_STATE: State = -1;
 pub struct TokensNFA<T> where T: Eq + Copy + Hash{
     pub states: State,
     pub finals: Vec<(T, TokenUses, State)>,
-    pub transitions: HashMap<(State, u8), Vec<State>>,
+    /// Byte range edges leaving each state, a class is a handful of ranges instead of one
+    /// edge per byte.
+    pub transitions: HashMap<State, Vec<(RangeInclusive<u8>, State)>>,
+    pub empty_transitions: HashMap<State, Vec<State>>,
 }
 
 impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
     pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Self {
-        let mut nfa = Self {states: 1, finals: Vec::new(), transitions: HashMap::new()};
+        let mut nfa = Self {states: 1, finals: Vec::new(), transitions: HashMap::new(), empty_transitions: HashMap::new()};
         
         for (token_name, attrs, regex) in tokens_regexs{
             let final_state = nfa.include_regex(INIT_STATE, regex);
@@ -26,26 +36,104 @@ impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
     }
 
     pub fn test_string(self, string: String) -> Option<T> {
-        return self.test(string, 0, INIT_STATE);
+        let mut current = self.epsilon_closure(vec![INIT_STATE]);
+        for char in string.as_bytes() {
+            let next = self.step(&current, *char);
+            if next.is_empty() {
+                return None;
+            }
+            current = self.epsilon_closure(next);
+        }
+        for (name, _, state) in &self.finals {
+            if current.contains(state) {
+                return Some(*name);
+            }
+        }
+        None
     }
 
-    fn test(&self, string: String, step: usize, current: State) -> Option<T>{
-        if step >= string.len() {
-            for (name, _, state) in &self.finals {
-                if *state == current {
-                    return Some(*name);
+    /// States reached from `state` over `byte`, without following epsilon edges.
+    pub fn next_states(&self, state: State, byte: u8) -> impl Iterator<Item = State> + '_ {
+        self.transitions.get(&state)
+            .into_iter()
+            .flatten()
+            .filter(move |(range, _)| range.contains(&byte))
+            .map(|(_, next)| *next)
+    }
+
+    /// States reached from any of `states` over `byte`, without following epsilon edges.
+    pub fn step(&self, states: &[State], byte: u8) -> Vec<State> {
+        let mut next = Vec::new();
+        for state in states {
+            for target in self.next_states(*state, byte) {
+                if !next.contains(&target) {
+                    next.push(target);
                 }
             }
-            return None;
         }
-        if let Some(nexts) = self.transitions.get(&(current, string.as_bytes()[step as usize])) {
-            for next in nexts {
-                if let Some(token) = self.test(string.clone(), step + 1, *next ) {
-                    return Some(token);
+        next
+    }
+
+    pub fn epsilon_closure(&self, mut states: Vec<State>) -> Vec<State> {
+        let mut pending = states.clone();
+        while let Some(state) = pending.pop() {
+            if let Some(nexts) = self.empty_transitions.get(&state) {
+                for next in nexts {
+                    if !states.contains(next) {
+                        states.push(*next);
+                        pending.push(*next);
+                    }
                 }
             }
         }
-        return None;
+        states.sort();
+        states.dedup();
+        states
+    }
+
+    /// Subset construction, the transition table of the DFA and the NFA states each of
+    /// its states stands for.
+    pub(crate) fn subsets(&self) -> (Vec<[State; 256]>, Vec<Vec<State>>) {
+        let mut table: Vec<[State; 256]> = vec![[ERROR_STATE; 256]];
+        let mut states = vec![self.epsilon_closure(vec![INIT_STATE])];
+        let mut indexes: HashMap<Vec<State>, State> = HashMap::from([(states[0].clone(), INIT_STATE)]);
+        let mut total_states = 1;
+
+        for i in 0.. {
+            if i == total_states {
+                break;
+            }
+            let edges: Vec<&(RangeInclusive<u8>, State)> = states[i].iter()
+                .filter_map(|current| self.transitions.get(current))
+                .flatten()
+                .collect();
+            for interval in disjoint_intervals(edges.iter().map(|(range, _)| range)) {
+                let mut new_state = Vec::new();
+                for (range, next) in &edges {
+                    if range.contains(interval.start()) && !new_state.contains(next) {
+                        new_state.push(*next);
+                    }
+                }
+                if new_state.is_empty() {
+                    continue;
+                }
+                let new_state = self.epsilon_closure(new_state);
+                let index = match indexes.get(&new_state) {
+                    Some(index) => *index,
+                    None => {
+                        indexes.insert(new_state.clone(), total_states as State);
+                        states.push(new_state);
+                        table.push([ERROR_STATE; 256]);
+                        total_states += 1;
+                        total_states as State - 1
+                    }
+                };
+                for letter in interval {
+                    table[i][letter as usize] = index;
+                }
+            }
+        }
+        (table, states)
     }
 
     fn include_regex(&mut self,mut current_state: State, regex: Regex) -> State{
@@ -57,8 +145,7 @@ impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
                 return current_state;
             },
             Regex::Union(regexs) => {
-                let to = self.states;
-                self.states += 1;
+                let to = self.new_state();
                 for re in regexs {
                     let from = self.include_regex(current_state, re);
                     self.insert_empty_transition(from, to);
@@ -66,36 +153,63 @@ impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
                 return to;
             },
             Regex::Repeat(re) => {
-                let from = self.include_regex(current_state, *re);
-                self.insert_empty_transition(from, current_state);
-                return current_state;
+                // A fresh loop state, so the loop never goes back into edges of the enclosing regex.
+                let start = self.new_state();
+                self.insert_empty_transition(current_state, start);
+                let from = self.include_regex(start, *re);
+                self.insert_empty_transition(from, start);
+                return start;
+            },
+            Regex::Bounded(re, min, max) => {
+                if max.is_some_and(|max| max < min) {
+                    // Inverted bounds match nothing, the returned state is unreachable.
+                    return self.new_state();
+                }
+                for _ in 0..min {
+                    current_state = self.include_regex(current_state, (*re).clone());
+                }
+                let Some(max) = max else {
+                    return self.include_regex(current_state, Regex::Repeat(re));
+                };
+                let to = self.new_state();
+                for _ in min..max {
+                    self.insert_empty_transition(current_state, to);
+                    current_state = self.include_regex(current_state, (*re).clone());
+                }
+                self.insert_empty_transition(current_state, to);
+                return to;
+            },
+            Regex::Trailing(head, tail) => {
+                return self.include_regex(current_state, Regex::Concat(vec![*head, *tail]));
+            },
+            Regex::Capture(_, re) => {
+                return self.include_regex(current_state, *re);
+            },
+            Regex::Intersection(_) | Regex::Complement(_) => {
+                return self.include_dfa(current_state, regex);
             },
             Regex::Word(word) => {
                 for char in word {
-                    let next = self.states;
-                self.states += 1;
-                if self.transitions.contains_key(&(current_state, char)) {
-                    self.transitions.get_mut(&(current_state, char)).unwrap().push(next);
-                } else {
-                    self.transitions.insert((current_state, char), vec![next]);
-                }
-                current_state = next;
+                    let next = self.new_state();
+                    self.insert_transition(current_state, char..=char, next);
+                    current_state = next;
                 }
                 return current_state;
             },
             Regex::Char(char) => {
-                let next = self.states;
-                self.states += 1;
-                if self.transitions.contains_key(&(current_state, char)) {
-                    self.transitions.get_mut(&(current_state, char)).unwrap().push(next);
-                } else {
-                    self.transitions.insert((current_state, char), vec![next]);
+                let next = self.new_state();
+                self.insert_transition(current_state, char..=char, next);
+                return next;
+            },
+            Regex::Class(class) => {
+                let next = self.new_state();
+                for range in class.ranges() {
+                    self.insert_transition(current_state, range, next);
                 }
                 return next;
             },
             Regex::Empty => {
-                let to = self.states;
-                self.states += 1;
+                let to = self.new_state();
                 self.insert_empty_transition(current_state, to);
                 return to;
             }
@@ -103,14 +217,211 @@ impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
         
     }
 
-    fn insert_empty_transition(&mut self, from: State, to: State){
-        for (_, v) in self.transitions.iter_mut() {
-            for states in v {
-                if *states == from {
-                    *states = to;
+    /// Intersections and complements have no Thompson construction, so the regex is turned
+    /// into a DFA by derivatives and its states are copied into the NFA.
+    fn include_dfa(&mut self, current_state: State, regex: Regex) -> State {
+        let (table, finals) = dfa_from_derivatives(vec![((), TokenUses::Default, regex)]);
+        let base = self.states;
+        self.states += table.len() as State;
+        let to = self.states;
+        self.states += 1;
+
+        self.insert_empty_transition(current_state, base);
+        for (state, row) in table.iter().enumerate() {
+            // Runs of bytes going to the same state become a single range edge.
+            let mut start = 0;
+            for char in 1..=256 {
+                if char == 256 || row[char] != row[start] {
+                    if row[start] != ERROR_STATE {
+                        self.insert_transition(base + state as State, start as u8..=(char - 1) as u8, base + row[start]);
+                    }
+                    start = char;
                 }
             }
         }
+        for (_, _, state) in finals {
+            self.insert_empty_transition(base + state, to);
+        }
+        to
+    }
+
+    fn new_state(&mut self) -> State {
+        self.states += 1;
+        self.states - 1
+    }
+
+    fn insert_transition(&mut self, from: State, range: RangeInclusive<u8>, to: State) {
+        self.transitions.entry(from).or_default().push((range, to));
+    }
+
+    fn insert_empty_transition(&mut self, from: State, to: State){
+        self.empty_transitions.entry(from).or_default().push(to);
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Construction {
+    /// Thompson style `TokensNFA` followed by the subset construction.
+    #[default]
+    Subset,
+    /// Brzozowski derivatives of the token regexs, without an intermediate NFA.
+    Derivatives
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DFABuilder {
+    construction: Construction
+}
+
+impl DFABuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn construction(mut self, construction: Construction) -> Self {
+        self.construction = construction;
+        self
+    }
+
+    pub fn build<T>(self, tokens_regexs: Vec<(T, TokenUses, Regex)>) -> TokensDFA<T>
+        where T: Eq + Copy + Hash {
+        let (lexeme_rules, rules) = LexemeRules::with_declarations(&tokens_regexs);
+        let names: Vec<T> = tokens_regexs.iter().map(|(name, _, _)| *name).collect();
+        // Built over declaration indexes, so each state knows which declaration it accepts.
+        let declarations = tokens_regexs.into_iter()
+            .enumerate()
+            .map(|(index, (_, mask, regex))| (index, mask, regex))
+            .collect();
+        let afd = match self.construction {
+            Construction::Subset => TokensDFA::from_nfa(TokensNFA::new(declarations)),
+            Construction::Derivatives => dfa_from_derivatives(declarations),
+        };
+        let mut dfa = TokensDFA::from_table(afd, &names, &rules);
+        dfa.lexeme_rules = lexeme_rules;
+        dfa
+    }
+
+    /// Builds the DFA along with the conflicts and shadowed tokens of the spec, the states
+    /// of the conflicts are those of the returned DFA.
+    pub fn build_with_warnings<T>(self, tokens_regexs: Vec<(T, TokenUses, Regex)>) -> (TokensDFA<T>, Vec<LexerSpecWarning<T>>)
+        where T: Eq + Copy + Hash {
+        let warnings = spec_warnings(&tokens_regexs);
+        let dfa = self.build(tokens_regexs);
+        let warnings = warnings.into_iter()
+            .map(|warning| match warning {
+                LexerSpecWarning::Conflict { example, tokens, winner, .. } => {
+                    let state = example.iter().fold(INIT_STATE, |state, byte| dfa.next(state, *byte));
+                    LexerSpecWarning::Conflict { state, example, tokens, winner }
+                },
+                warning => warning,
+            })
+            .collect();
+        (dfa, warnings)
+    }
+}
+
+
+/// The two halves of a `head/tail` token, used to find where the lexeme ends once the
+/// whole `head tail` has been matched.
+pub struct TrailingContext {
+    pub(crate) head: TokensDFA<()>,
+    pub(crate) tail: TokensDFA<()>,
+}
+
+impl TrailingContext {
+    fn new(head: &Regex, tail: &Regex) -> Self {
+        let single = |regex: &Regex| DFABuilder::new()
+            .construction(Construction::Derivatives)
+            .build(vec![((), TokenUses::Default, regex.clone())]);
+        Self { head: single(head), tail: single(tail) }
+    }
+
+    /// Length of the longest prefix of `matched` accepted by the head whose remainder is
+    /// accepted by the tail.
+    fn head_length(&self, matched: &[u8]) -> Option<usize> {
+        (0..=matched.len()).rev().find(|split| {
+            self.head.accepts(&matched[..*split]) && self.tail.accepts(&matched[*split..])
+        })
+    }
+}
+
+
+/// What decides the lexeme of a token once its whole match is known: trailing contexts
+/// to give back and named groups to replay.
+pub struct LexemeRules<T> where T: Eq + Copy + Hash {
+    /// Trailing contexts by the index of their declaration in `regexs`, a token may be
+    /// declared several times with different contexts.
+    pub trailing_contexts: HashMap<usize, TrailingContext>,
+    /// Regexs with named groups by the index of their declaration in `regexs`, replayed
+    /// on the lexemes of that declaration.
+    pub capture_regexs: HashMap<usize, Regex>,
+    /// The tokens the rules come from, plus the ends of block comments, enough to build
+    /// the rules again.
+    pub regexs: Vec<(T, TokenUses, Regex)>,
+}
+
+impl<T> LexemeRules<T> where T: Eq + Copy + Hash {
+    pub fn new(tokens_regexs: &[(T, TokenUses, Regex)]) -> Self {
+        Self::with_declarations(tokens_regexs).0
+    }
+
+    /// Also gives, for each declaration, the index in `regexs` of the rules applying to
+    /// its lexemes, `None` when the lexeme is the whole match.
+    ///
+    /// Panics if a declaration has a trailing context anywhere but at its top.
+    pub(crate) fn with_declarations(tokens_regexs: &[(T, TokenUses, Regex)]) -> (Self, Vec<Option<usize>>) {
+        let mut rules = Self { trailing_contexts: HashMap::new(), capture_regexs: HashMap::new(), regexs: Vec::new() };
+        let mut declarations = Vec::new();
+        for (declaration, (name, mask, regex)) in tokens_regexs.iter().enumerate() {
+            let nested = match regex {
+                Regex::Trailing(head, tail) => head.has_trailing_context() || tail.has_trailing_context(),
+                regex => regex.has_trailing_context(),
+            };
+            assert!(!nested, "declaration {} has a trailing context below its top level", declaration);
+            let lexeme_regex = match regex {
+                Regex::Trailing(head, _) => head,
+                regex => regex,
+            };
+            if !matches!(regex, Regex::Trailing(..)) && !lexeme_regex.has_captures() && *mask != TokenUses::EndBlockComment {
+                declarations.push(None);
+                continue;
+            }
+            let index = rules.regexs.len();
+            rules.regexs.push((*name, *mask, regex.clone()));
+            let mut rule = None;
+            if let Regex::Trailing(head, tail) = regex {
+                rules.trailing_contexts.insert(index, TrailingContext::new(head, tail));
+                rule = Some(index);
+            }
+            if lexeme_regex.has_captures() {
+                rules.capture_regexs.insert(index, lexeme_regex.clone());
+                rule = Some(index);
+            }
+            declarations.push(rule);
+        }
+        (rules, declarations)
+    }
+
+    /// Regex closing block comments, the last declared one if there are several.
+    pub fn end_comment(&self) -> Option<&Regex> {
+        self.regexs.iter().rev().find(|(_, mask, _)| *mask == TokenUses::EndBlockComment).map(|(_, _, regex)| regex)
+    }
+
+    /// Spans of the named groups inside a lexeme accepted with the rules at `rule` in
+    /// `regexs`, empty for declarations without groups.
+    pub fn captures(&self, rule: Option<usize>, lexeme: &[u8]) -> Vec<(String, Range<usize>)> {
+        rule.and_then(|rule| self.capture_regexs.get(&rule))
+            .and_then(|regex| regex.captures(lexeme))
+            .unwrap_or_default()
+    }
+
+    /// How many bytes of a match accepted with the rules at `rule` in `regexs` belong to
+    /// the lexeme, declarations with a trailing context leave the context bytes unconsumed.
+    pub fn lexeme_length(&self, rule: Option<usize>, matched: &[u8]) -> usize {
+        rule.and_then(|rule| self.trailing_contexts.get(&rule))
+            .and_then(|context| context.head_length(matched))
+            .unwrap_or(matched.len())
     }
 }
 
@@ -118,31 +429,85 @@ impl<T> TokensNFA<T> where T: Eq + Copy + Hash{
 pub struct TokensDFA<T> where T: Eq + Copy + Hash {
     pub states: u32,
     pub finals: Vec<(Option<T>, TokenUses)>,
-    pub transitions: Vec<[State; 256]>
+    /// Equivalence class of each byte, bytes of a class lead to the same state from every state.
+    pub classes: [u8; 256],
+    pub class_count: usize,
+    /// One row of `class_count` states per state, indexed by class.
+    pub transitions: Vec<State>,
+    /// For each state, the index in `lexeme_rules.regexs` of the rules of the declaration
+    /// it accepts, if that declaration has any.
+    pub rules: Vec<Option<usize>>,
+    pub lexeme_rules: LexemeRules<T>,
 }
 
 impl<T> TokensDFA<T> where T: Eq + Copy + Hash {
     pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Self {
-        let afd = TokensDFA::from_nfa(TokensNFA::new(tokens_regexs));
+        DFABuilder::new().build(tokens_regexs)
+    }
 
+    /// `afd` accepts declaration indexes, `names` and `rules` give the token and the lexeme
+    /// rules of each declaration.
+    fn from_table(afd: Table<usize>, names: &[T], rules: &[Option<usize>]) -> Self {
         let states = afd.0.len() as u32;
         let mut finals = vec![(None, TokenUses::Default); states as usize];
+        let mut state_rules = vec![None; states as usize];
         
-        for (name, mask, state) in afd.1 {
-            finals[state as usize] = (Some(name), mask);
+        for (declaration, mask, state) in afd.1 {
+            finals[state as usize] = (Some(names[declaration]), mask);
+            state_rules[state as usize] = rules[declaration];
+        }
+        let (classes, class_count) = byte_classes(&afd.0);
+        let mut transitions = vec![ERROR_STATE; states as usize * class_count];
+        for (state, row) in afd.0.iter().enumerate() {
+            for (char, next) in row.iter().enumerate() {
+                transitions[state * class_count + classes[char] as usize] = *next;
+            }
         }
         
         Self { 
             states, 
             finals,
-            transitions: afd.0, 
+            classes,
+            class_count,
+            transitions, 
+            rules: state_rules,
+            lexeme_rules: LexemeRules::new(&[]),
         }
     }
 
+    /// State reached from `state` over `byte`.
+    pub fn next(&self, state: State, byte: u8) -> State {
+        self.transitions[state as usize * self.class_count + self.classes[byte as usize] as usize]
+    }
+
+    /// State reached from `state` over any byte of `class`.
+    pub fn next_by_class(&self, state: State, class: usize) -> State {
+        self.transitions[state as usize * self.class_count + class]
+    }
+
+    pub fn captures(&self, rule: Option<usize>, lexeme: &[u8]) -> Vec<(String, Range<usize>)> {
+        self.lexeme_rules.captures(rule, lexeme)
+    }
+
+    pub fn lexeme_length(&self, rule: Option<usize>, matched: &[u8]) -> usize {
+        self.lexeme_rules.lexeme_length(rule, matched)
+    }
+
+    fn accepts(&self, input: &[u8]) -> bool {
+        let mut state = INIT_STATE;
+        for char in input {
+            state = self.next(state, *char);
+            if state == ERROR_STATE {
+                return false;
+            }
+        }
+        self.finals[state as usize].0.is_some()
+    }
+
     pub fn test_string(&self, string: String) -> Option<T> {
         let mut state = INIT_STATE; 
         for char in string.as_bytes(){
-            state = self.transitions[state as usize][*char as usize];
+            state = self.next(state, *char);
             if state == ERROR_STATE {
                 return None;
             }
@@ -153,57 +518,48 @@ impl<T> TokensDFA<T> where T: Eq + Copy + Hash {
         return None;
     }
 
-    fn from_nfa(nfa: TokensNFA<T>) -> (Vec<[State; 256]>, Vec<(T, TokenUses, State)>) {
-        let finals = nfa.finals; 
-        let transitions = nfa.transitions;
-
-        let mut table: Vec<[State; 256]> = vec![[ERROR_STATE; 256]];
+    fn from_nfa(nfa: TokensNFA<T>) -> Table<T> {
+        let (table, subsets) = nfa.subsets();
         let mut new_finals: Vec<(T, TokenUses, State)> = Vec::new();
-
-        let mut states = vec![vec![INIT_STATE]];
-        let mut total_states = 1;
-
-        for i in 0.. {
-            if i == total_states {
-                break;
-            }
-
-            for letter in 0..=255 {
-                let mut new_state = Vec::new();
-                for current in &states[i] {
-                    if transitions.contains_key(&(*current, letter)) {
-                        for next in transitions.get(&(*current, letter)).unwrap() {
-                            if !new_state.contains(next) {
-                                new_state.push(*next);
-                            }
-                        }
-                    }
-                }
-                if new_state.is_empty() {
-                    continue;
-                }
-                new_state.sort();
-                if states.contains(&new_state) {
-                    for index in 0..total_states {
-                        if states[index] == new_state {
-                            table[i][letter as usize] = index as State;
-                            break;
-                        }
-                    }
-                } else {
-                    for (name, attr, other) in &finals {
-                        if new_state.contains(&other) {
-                            new_finals.push((*name, *attr, total_states as State));
-                            break;
-                        } 
-                    }
-                    states.push(new_state);
-                    table.push([ERROR_STATE; 256]);
-                    table[i][letter as usize] = total_states as State;                    
-                    total_states += 1;
-                }
+        for (i, subset) in subsets.iter().enumerate() {
+            // Checked on every subset, the initial one included, so nullable tokens accept it.
+            if let Some((name, attr, _)) = nfa.finals.iter().find(|(_, _, other)| subset.contains(other)) {
+                new_finals.push((*name, *attr, i as State));
             }
         }
         return (table, new_finals);
     }
-}
\ No newline at end of file
+}
+
+/// Splits overlapping byte ranges at every range boundary, so each resulting interval is
+/// either inside or outside each of the given ranges.
+fn disjoint_intervals<'r, I>(ranges: I) -> Vec<RangeInclusive<u8>>
+    where I: Iterator<Item = &'r RangeInclusive<u8>> {
+    let mut bounds: Vec<u16> = Vec::new();
+    for range in ranges {
+        bounds.push(*range.start() as u16);
+        bounds.push(*range.end() as u16 + 1);
+    }
+    bounds.sort();
+    bounds.dedup();
+    bounds.windows(2).map(|pair| pair[0] as u8..=(pair[1] - 1) as u8).collect()
+}
+
+/// Groups the bytes no row tells apart, returns the class of each byte and the number of
+/// classes. Classes are numbered by their smallest byte.
+fn byte_classes(rows: &[[State; 256]]) -> ([u8; 256], usize) {
+    let mut classes = [0u8; 256];
+    let mut count = 1;
+    for row in rows {
+        let mut refined: HashMap<(u8, State), u8> = HashMap::new();
+        for char in 0..256 {
+            let next = refined.len() as u8;
+            classes[char] = *refined.entry((classes[char], row[char])).or_insert(next);
+        }
+        count = refined.len();
+        if count == 256 {
+            break;
+        }
+    }
+    (classes, count)
+}
@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use crate::lexica::{
+    automata::{Construction, DFABuilder, ERROR_STATE, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+type Next<'n, 'a> = &'n mut dyn FnMut(&mut Replay<'a>, usize) -> bool;
+
+impl Regex {
+    pub fn has_captures(&self) -> bool {
+        match self {
+            Regex::Capture(..) => true,
+            Regex::Concat(regexs) | Regex::Union(regexs) | Regex::Intersection(regexs) => regexs.iter().any(Regex::has_captures),
+            Regex::Repeat(re) | Regex::Bounded(re, _, _) | Regex::Complement(re) => re.has_captures(),
+            Regex::Trailing(head, tail) => head.has_captures() || tail.has_captures(),
+            Regex::Word(_) | Regex::Char(_) | Regex::Class(_) | Regex::Empty => false,
+        }
+    }
+
+    /// Matches the whole `input` again and returns the span of every named group that took
+    /// part in the match, ordered by position.
+    ///
+    /// Alternatives are tried in order and repetitions are greedy, a group matched several
+    /// times reports its last iteration. Groups inside intersections or complements are
+    /// not reported.
+    pub fn captures(&self, input: &[u8]) -> Option<Vec<(String, Range<usize>)>> {
+        let mut replay = Replay { input, groups: Vec::new() };
+        if !replay.walk(self, 0, &mut |replay, end| end == replay.input.len()) {
+            return None;
+        }
+        let mut groups: Vec<(String, Range<usize>)> = Vec::new();
+        for (name, span) in replay.groups.into_iter().rev() {
+            if !groups.iter().any(|(other, _)| *other == name) {
+                groups.push((name, span));
+            }
+        }
+        groups.sort_by_key(|(_, span)| (span.start, span.end));
+        Some(groups)
+    }
+}
+
+/// Backtracking matcher, `next` is called with the end of every way the current regex can
+/// match and the walk stops as soon as it returns true.
+struct Replay<'a> {
+    input: &'a [u8],
+    groups: Vec<(String, Range<usize>)>,
+}
+
+impl<'a> Replay<'a> {
+    fn walk(&mut self, regex: &Regex, pos: usize, next: Next<'_, 'a>) -> bool {
+        match regex {
+            Regex::Concat(regexs) => self.sequence(regexs, pos, next),
+            Regex::Trailing(head, tail) => self.walk(head, pos, &mut |replay, end| replay.walk(tail, end, next)),
+            Regex::Union(regexs) => regexs.iter().any(|re| self.walk(re, pos, next)),
+            Regex::Repeat(re) => self.repeat(re, 0, 0, None, pos, next),
+            Regex::Bounded(re, min, max) => self.repeat(re, 0, *min, *max, pos, next),
+            Regex::Capture(name, re) => self.walk(re, pos, &mut |replay, end| {
+                replay.groups.push((name.clone(), pos..end));
+                if next(replay, end) {
+                    return true;
+                }
+                replay.groups.pop();
+                false
+            }),
+            Regex::Intersection(_) | Regex::Complement(_) => {
+                for end in self.ends(regex, pos).into_iter().rev() {
+                    if next(self, end) {
+                        return true;
+                    }
+                }
+                false
+            },
+            Regex::Word(word) => self.input[pos..].starts_with(word) && next(self, pos + word.len()),
+            Regex::Char(c) => self.input.get(pos) == Some(c) && next(self, pos + 1),
+            Regex::Class(class) => self.input.get(pos).is_some_and(|c| class.contains(*c)) && next(self, pos + 1),
+            Regex::Empty => next(self, pos),
+        }
+    }
+
+    fn sequence(&mut self, regexs: &[Regex], pos: usize, next: Next<'_, 'a>) -> bool {
+        match regexs.split_first() {
+            None => next(self, pos),
+            Some((first, rest)) => self.walk(first, pos, &mut |replay, end| replay.sequence(rest, end, next)),
+        }
+    }
+
+    fn repeat(&mut self, re: &Regex, count: u32, min: u32, max: Option<u32>, pos: usize, next: Next<'_, 'a>) -> bool {
+        if max.is_none_or(|max| count < max) {
+            // Iterations that match nothing are only useful to reach `min`.
+            let more = self.walk(re, pos, &mut |replay, end| {
+                (end > pos || count < min) && replay.repeat(re, count + 1, min, max, end, next)
+            });
+            if more {
+                return true;
+            }
+        }
+        count >= min && next(self, pos)
+    }
+
+    /// End positions of the matches of `regex` starting at `pos`, found with its DFA.
+    fn ends(&self, regex: &Regex, pos: usize) -> Vec<usize> {
+        let dfa = DFABuilder::new()
+            .construction(Construction::Derivatives)
+            .build(vec![((), TokenUses::Default, regex.clone())]);
+        let mut ends = Vec::new();
+        let mut state = INIT_STATE;
+        for end in pos..=self.input.len() {
+            if dfa.finals[state as usize].0.is_some() {
+                ends.push(end);
+            }
+            let Some(c) = self.input.get(end) else { break };
+            state = dfa.next(state, *c);
+            if state == ERROR_STATE {
+                break;
+            }
+        }
+        ends
+    }
+}
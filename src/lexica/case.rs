@@ -0,0 +1,91 @@
+use crate::lexica::{class::CharClass, regex::Regex};
+
+impl Regex {
+    /// Returns a copy of the regex that matches letters regardless of their case.
+    ///
+    /// Bytes, words and classes are folded over ASCII letters; words that are valid
+    /// UTF-8 also fold code points with a single character upper and lower case form.
+    pub fn ignore_case(self) -> Regex {
+        match self {
+            Regex::Concat(regexs) => Regex::Concat(regexs.into_iter().map(Regex::ignore_case).collect()),
+            Regex::Union(regexs) => Regex::Union(regexs.into_iter().map(Regex::ignore_case).collect()),
+            Regex::Repeat(re) => Regex::Repeat(Box::new(re.ignore_case())),
+            Regex::Bounded(re, min, max) => Regex::Bounded(Box::new(re.ignore_case()), min, max),
+            Regex::Intersection(regexs) => Regex::Intersection(regexs.into_iter().map(Regex::ignore_case).collect()),
+            Regex::Complement(re) => Regex::Complement(Box::new(re.ignore_case())),
+            Regex::Capture(name, re) => Regex::Capture(name, Box::new(re.ignore_case())),
+            Regex::Trailing(head, tail) => Regex::Trailing(Box::new(head.ignore_case()), Box::new(tail.ignore_case())),
+            Regex::Word(word) => fold_word(word),
+            Regex::Char(c) => fold_byte(c),
+            Regex::Class(class) => Regex::Class(fold_class(&class)),
+            Regex::Empty => Regex::Empty,
+        }
+    }
+
+    pub fn word_ci(word: &str) -> Regex {
+        Regex::from_word(word).ignore_case()
+    }
+}
+
+fn fold_byte(byte: u8) -> Regex {
+    if byte.is_ascii_alphabetic() {
+        return Regex::Class(CharClass::from_bytes(&[byte.to_ascii_lowercase(), byte.to_ascii_uppercase()]));
+    }
+    Regex::Char(byte)
+}
+
+fn fold_class(class: &CharClass) -> CharClass {
+    let lower = class.intersection(&CharClass::from_range(b'a'..=b'z'));
+    let upper = class.intersection(&CharClass::from_range(b'A'..=b'Z'));
+    let lower_as_upper = CharClass::from_ranges(lower.ranges().map(|range| range.start() - 32..=range.end() - 32));
+    let upper_as_lower = CharClass::from_ranges(upper.ranges().map(|range| range.start() + 32..=range.end() + 32));
+    class.union(&lower_as_upper).union(&upper_as_lower)
+}
+
+fn fold_word(word: Vec<u8>) -> Regex {
+    let items: Vec<Regex> = match std::str::from_utf8(&word) {
+        Ok(text) => text.chars().map(fold_char).collect(),
+        Err(_) => word.into_iter().map(fold_byte).collect(),
+    };
+
+    let mut merged: Vec<Regex> = Vec::new();
+    for item in items {
+        match (merged.last_mut(), item) {
+            (Some(Regex::Word(last)), Regex::Word(bytes)) => last.extend(bytes),
+            (_, item) => merged.push(item),
+        }
+    }
+    if merged.len() == 1 {
+        return merged.pop().unwrap();
+    }
+    Regex::Concat(merged)
+}
+
+fn fold_char(c: char) -> Regex {
+    if c.is_ascii() {
+        return match fold_byte(c as u8) {
+            Regex::Char(byte) => Regex::Word(vec![byte]),
+            folded => folded,
+        };
+    }
+    let mut variants = vec![c];
+    for other in [single_char(c.to_lowercase()), single_char(c.to_uppercase())].into_iter().flatten() {
+        if !variants.contains(&other) {
+            variants.push(other);
+        }
+    }
+    let mut encoded = variants.into_iter().map(|variant| Regex::from_word(variant.encode_utf8(&mut [0; 4])));
+    if encoded.len() == 1 {
+        return encoded.next().unwrap();
+    }
+    Regex::Union(encoded.collect())
+}
+
+fn single_char<I>(mut chars: I) -> Option<char>
+    where I: Iterator<Item = char> {
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(c)
+}
@@ -0,0 +1,108 @@
+use std::ops::RangeInclusive;
+
+/// A set of bytes stored as sorted, disjoint and non adjacent inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct CharClass {
+    ranges: Vec<(u8, u8)>
+}
+
+impl CharClass {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn full() -> Self {
+        Self { ranges: vec![(0, 255)] }
+    }
+
+    pub fn from_range(range: RangeInclusive<u8>) -> Self {
+        Self::from_ranges(vec![range])
+    }
+
+    pub fn from_ranges<I>(ranges: I) -> Self
+        where I: IntoIterator<Item = RangeInclusive<u8>> {
+        let mut class = Self::new();
+        for range in ranges {
+            if range.start() <= range.end() {
+                class.ranges.push((*range.start(), *range.end()));
+            }
+        }
+        class.normalize();
+        class
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_ranges(bytes.iter().map(|byte| *byte..=*byte))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|(low, high)| (*high - *low) as usize + 1).sum()
+    }
+
+    pub fn contains(&self, byte: u8) -> bool {
+        self.ranges.iter().any(|(low, high)| *low <= byte && byte <= *high)
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u8>> + '_ {
+        self.ranges.iter().map(|(low, high)| *low..=*high)
+    }
+
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.ranges().flatten()
+    }
+
+    pub fn union(&self, other: &CharClass) -> CharClass {
+        let mut class = self.clone();
+        class.ranges.extend(other.ranges.iter().copied());
+        class.normalize();
+        class
+    }
+
+    pub fn intersection(&self, other: &CharClass) -> CharClass {
+        let mut ranges = Vec::new();
+        for (low, high) in &self.ranges {
+            for (other_low, other_high) in &other.ranges {
+                let start = (*low).max(*other_low);
+                let end = (*high).min(*other_high);
+                if start <= end {
+                    ranges.push(start..=end);
+                }
+            }
+        }
+        CharClass::from_ranges(ranges)
+    }
+
+    pub fn negate(&self) -> CharClass {
+        let mut ranges = Vec::new();
+        let mut next: u16 = 0;
+        for (low, high) in &self.ranges {
+            if (*low as u16) > next {
+                ranges.push(next as u8..=*low - 1);
+            }
+            next = *high as u16 + 1;
+        }
+        if next <= 255 {
+            ranges.push(next as u8..=255);
+        }
+        CharClass::from_ranges(ranges)
+    }
+
+    fn normalize(&mut self) {
+        self.ranges.sort();
+        let mut merged: Vec<(u8, u8)> = Vec::with_capacity(self.ranges.len());
+        for (low, high) in self.ranges.drain(..) {
+            if let Some(last) = merged.last_mut() {
+                if low as u16 <= last.1 as u16 + 1 {
+                    last.1 = last.1.max(high);
+                    continue;
+                }
+            }
+            merged.push((low, high));
+        }
+        self.ranges = merged;
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::{self, Debug, Write};
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{TokensDFA, ERROR_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodegenError {
+    /// Named groups are replayed with the regex at lexing time, which a generated lexer
+    /// doesn't carry. Holds the token, as printed by `Debug`.
+    Captures(String),
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::Captures(token) => write!(f, "token {} has named groups, generated lexers don't support them", token),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+/// Writes the Rust source of a lexer module for a token spec: static tables of the minimized
+/// DFA and a `Lexer` yielding the same tokens `LexicalAnalysis` would, without any of the
+/// regex or automata code. It lexes a byte slice held in memory, so unlike `LexicalAnalysis`
+/// it has no limit on the length of a lexeme.
+///
+/// Tokens are written as `{token_type}::{token:?}`, so `T` is expected to be a fieldless enum
+/// and `token_type` a path to it from the generated module, e.g. `crate::tokens::Kind`.
+#[derive(Debug, Clone)]
+pub struct LexerGenerator {
+    token_type: String,
+}
+
+impl LexerGenerator {
+    pub fn new(token_type: &str) -> Self {
+        Self { token_type: token_type.to_string() }
+    }
+
+    pub fn generate<T>(&self, tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Result<String, CodegenError>
+        where T: Eq + Copy + Hash + Debug {
+        if let Some((name, _, _)) = tokens_regexs.iter().find(|(_, _, regex)| regex.has_captures()) {
+            return Err(CodegenError::Captures(format!("{:?}", name)));
+        }
+        let dfa = TokensDFA::new(tokens_regexs).minimize();
+        let path = &self.token_type;
+        let mut out = String::new();
+
+        out.push_str(HEADER);
+        let _ = writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]\npub struct Token {{\n    pub t_type: {},\n    pub t_name: Option<String>,\n}}\n", path);
+        out.push_str(RUNTIME);
+
+        let _ = writeln!(out, "static TOKENS: Table = {};\n", table(&dfa));
+        let accepts: Vec<String> = dfa.finals.iter().zip(&dfa.rules)
+            .map(|final_state| match final_state {
+                ((Some(token), mask), rule) => format!("Some(({}::{:?}, Uses::{:?}, {:?}))", path, token, mask, rule),
+                ((None, _), _) => "None".to_string(),
+            })
+            .collect();
+        let _ = writeln!(out, "static ACCEPTS: [Option<({}, Uses, Option<usize>)>; {}] = [\n    {},\n];\n", path, accepts.len(), accepts.join(",\n    "));
+
+        // The same end of comment automaton `LexicalAnalysis` builds, stopping on its last accepting state.
+        let end_comment = dfa.lexeme_rules.end_comment().map(|regex| {
+            let end = TokensDFA::new(vec![(0, TokenUses::EndBlockComment, regex.clone())]);
+            let final_state = end.finals.iter().rposition(|(token, _)| token.is_some()).map_or(ERROR_STATE, |state| state as i32);
+            format!("Some(({}, {}))", table(&end), final_state)
+        });
+        let _ = writeln!(out, "static END_COMMENT: Option<(Table, i32)> = {};\n", end_comment.unwrap_or_else(|| "None".to_string()));
+
+        // Trailing contexts by lexeme rule, the third field of `ACCEPTS`.
+        let mut trailing: Vec<_> = dfa.lexeme_rules.trailing_contexts.iter().collect();
+        trailing.sort_by_key(|(rule, _)| **rule);
+        let trailing: Vec<String> = trailing.into_iter()
+            .map(|(rule, context)| format!("({}, {}, {})", rule, table(&context.head), table(&context.tail)))
+            .collect();
+        let _ = writeln!(out, "static TRAILING: &[(usize, Table, Table)] = &[{}];\n", trailing.join(", "));
+
+        out.push_str(LEXER);
+        Ok(out)
+    }
+}
+
+/// A `Table` literal of the generated module.
+fn table<T>(dfa: &TokensDFA<T>) -> String where T: Eq + Copy + Hash {
+    let lines = |values: Vec<String>, per_line: usize| values.chunks(per_line)
+        .map(|line| line.join(", "))
+        .collect::<Vec<_>>()
+        .join(",\n        ");
+    format!(
+        "Table {{\n    class_count: {},\n    classes: [\n        {},\n    ],\n    transitions: &[\n        {},\n    ],\n    accepting: &[\n        {},\n    ],\n}}",
+        dfa.class_count,
+        lines(dfa.classes.iter().map(|class| class.to_string()).collect(), 32),
+        lines(dfa.transitions.iter().map(|next| next.to_string()).collect(), dfa.class_count),
+        lines(dfa.finals.iter().map(|(token, _)| token.is_some().to_string()).collect(), 16),
+    )
+}
+
+const HEADER: &str = "\
+// Generated by `compiler_create::lexica::codegen::LexerGenerator`, do not edit.
+
+";
+
+const RUNTIME: &str = "\
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Uses {
+    Default,
+    GetLexeme,
+    IgnoreThis,
+    InitBlockComment,
+    EndBlockComment,
+    InitInlineComment,
+}
+
+const ERROR: i32 = -1;
+
+/// DFA rows indexed by byte class.
+struct Table {
+    class_count: usize,
+    classes: [u8; 256],
+    transitions: &'static [i32],
+    accepting: &'static [bool],
+}
+
+impl Table {
+    fn next(&self, state: i32, byte: u8) -> i32 {
+        self.transitions[state as usize * self.class_count + self.classes[byte as usize] as usize]
+    }
+
+    fn accepts(&self, input: &[u8]) -> bool {
+        let mut state = 0;
+        for byte in input {
+            state = self.next(state, *byte);
+            if state == ERROR {
+                return false;
+            }
+        }
+        self.accepting[state as usize]
+    }
+}
+
+";
+
+const LEXER: &str = "\
+pub struct Lexer<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Lexer { input, position: 0 }
+    }
+
+    /// The longest match wins, ties go to the first declared token.
+    pub fn next_token(&mut self) -> Option<Token> {
+        loop {
+            if self.position >= self.input.len() {
+                return None;
+            }
+            let start = self.position;
+            let mut state = 0;
+            let mut accepted = None;
+            for (index, byte) in self.input[start..].iter().enumerate() {
+                state = TOKENS.next(state, *byte);
+                if state == ERROR {
+                    break;
+                }
+                if let Some(token) = ACCEPTS[state as usize] {
+                    accepted = Some((token, index + 1));
+                }
+            }
+            let Some(((t_type, uses, rule), mut length)) = accepted else {
+                panic!(\"unexpected character {:?} at byte {}\", self.input[start] as char, start);
+            };
+            if let Some((_, head, tail)) = TRAILING.iter().find(|(index, _, _)| Some(*index) == rule) {
+                let matched = &self.input[start..start + length];
+                if let Some(split) = (0..=length).rev().find(|split| head.accepts(&matched[..*split]) && tail.accepts(&matched[*split..])) {
+                    length = split;
+                }
+            }
+            self.position = start + length;
+
+            match uses {
+                Uses::IgnoreThis => {},
+                Uses::InitBlockComment => self.skip_block_comment(),
+                Uses::InitInlineComment => self.skip_inline_comment(),
+                Uses::EndBlockComment => panic!(\"comment not started at byte {}\", start),
+                Uses::GetLexeme => {
+                    let t_name = String::from_utf8_lossy(&self.input[start..self.position]).into_owned();
+                    return Some(Token { t_type, t_name: Some(t_name) });
+                },
+                Uses::Default => return Some(Token { t_type, t_name: None }),
+            }
+        }
+    }
+
+    fn skip_inline_comment(&mut self) {
+        while self.position < self.input.len() && self.input[self.position] != b'\\n' {
+            self.position += 1;
+        }
+    }
+
+    /// Restarts the end of comment automaton on every byte it rejects, that byte included.
+    fn skip_block_comment(&mut self) {
+        let Some((end, final_state)) = &END_COMMENT else {
+            panic!(\"a block comment was started but no token ends block comments\");
+        };
+        loop {
+            let mut state = 0;
+            loop {
+                if state == *final_state {
+                    return;
+                }
+                let Some(byte) = self.input.get(self.position) else {
+                    panic!(\"comment not closed at byte {}\", self.position);
+                };
+                state = end.next(state, *byte);
+                self.position += 1;
+                if state == ERROR {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+";
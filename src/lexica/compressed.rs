@@ -0,0 +1,153 @@
+use std::fmt;
+use std::hash::Hash;
+use std::mem::size_of;
+
+use crate::lexica::{
+    automata::{LexemeRules, State, TokensDFA, ERROR_STATE, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// A `TokensDFA` with its class indexed rows packed the way flex does: each state keeps only
+/// the entries that differ from a default state, and the rows are overlapped in one `next`
+/// array at a per state `base` offset, `check` telling which state owns each slot.
+pub struct CompressedDFA<T> where T: Eq + Copy + Hash {
+    pub states: u32,
+    pub finals: Vec<(Option<T>, TokenUses)>,
+    pub classes: [u8; 256],
+    pub class_count: usize,
+    pub base: Vec<usize>,
+    /// State whose row holds the entries a state doesn't store, `ERROR_STATE` ends the chain.
+    pub default: Vec<State>,
+    pub next: Vec<State>,
+    pub check: Vec<State>,
+    /// Lexeme rules of the declaration each state accepts, as in `TokensDFA::rules`.
+    pub rules: Vec<Option<usize>>,
+    pub lexeme_rules: LexemeRules<T>,
+}
+
+impl<T> CompressedDFA<T> where T: Eq + Copy + Hash {
+    /// Packs the minimized DFA of the tokens.
+    pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Self {
+        Self::from_dfa(TokensDFA::new(tokens_regexs).minimize())
+    }
+
+    pub fn from_dfa(dfa: TokensDFA<T>) -> Self {
+        let states = dfa.states as usize;
+        let row = |state: usize| &dfa.transitions[state * dfa.class_count..(state + 1) * dfa.class_count];
+
+        let mut default = Vec::with_capacity(states);
+        let mut entries: Vec<Vec<(usize, State)>> = Vec::with_capacity(states);
+        for state in 0..states {
+            // Without a default only the edges to a state are stored, a default is worth it
+            // when fewer entries differ from it. Only earlier states are candidates, so
+            // chains always end.
+            let mut stored: Vec<(usize, State)> = row(state).iter().enumerate()
+                .filter(|(_, next)| **next != ERROR_STATE)
+                .map(|(class, next)| (class, *next))
+                .collect();
+            let mut chosen = ERROR_STATE;
+            for candidate in 0..state {
+                let differing: Vec<(usize, State)> = row(state).iter().zip(row(candidate)).enumerate()
+                    .filter(|(_, (next, other))| next != other)
+                    .map(|(class, (next, _))| (class, *next))
+                    .collect();
+                if differing.len() < stored.len() {
+                    stored = differing;
+                    chosen = candidate as State;
+                }
+            }
+            default.push(chosen);
+            entries.push(stored);
+        }
+
+        // First fit: the lowest base where all the entries of the state land on free slots.
+        let mut base = vec![0; states];
+        let mut next: Vec<State> = Vec::new();
+        let mut check: Vec<State> = Vec::new();
+        for (state, stored) in entries.iter().enumerate() {
+            let fits = |offset: usize| stored.iter().all(|(class, _)| check.get(offset + class).is_none_or(|owner| *owner == ERROR_STATE));
+            let offset = (0..).find(|offset| fits(*offset)).unwrap();
+            let end = offset + stored.last().map_or(0, |(class, _)| class + 1);
+            if end > next.len() {
+                next.resize(end, ERROR_STATE);
+                check.resize(end, ERROR_STATE);
+            }
+            for (class, target) in stored {
+                next[offset + class] = *target;
+                check[offset + class] = state as State;
+            }
+            base[state] = offset;
+        }
+
+        Self {
+            states: dfa.states,
+            finals: dfa.finals,
+            classes: dfa.classes,
+            class_count: dfa.class_count,
+            base,
+            default,
+            next,
+            check,
+            rules: dfa.rules,
+            lexeme_rules: dfa.lexeme_rules,
+        }
+    }
+
+    /// State reached from `state` over `byte`, following the default chain for entries the
+    /// state doesn't store.
+    pub fn next(&self, mut state: State, byte: u8) -> State {
+        let class = self.classes[byte as usize] as usize;
+        while state != ERROR_STATE {
+            let slot = self.base[state as usize] + class;
+            if self.check.get(slot) == Some(&state) {
+                return self.next[slot];
+            }
+            state = self.default[state as usize];
+        }
+        ERROR_STATE
+    }
+
+    pub fn test_string(&self, string: String) -> Option<T> {
+        let mut state = INIT_STATE;
+        for char in string.as_bytes() {
+            state = self.next(state, *char);
+            if state == ERROR_STATE {
+                return None;
+            }
+        }
+        self.finals[state as usize].0
+    }
+
+    pub fn report(&self) -> CompressionReport {
+        let states = self.states as usize;
+        CompressionReport {
+            states,
+            class_count: self.class_count,
+            full_bytes: states * 256 * size_of::<State>(),
+            classes_bytes: 256 + states * self.class_count * size_of::<State>(),
+            packed_bytes: 256 + states * (size_of::<usize>() + size_of::<State>()) + self.next.len() * 2 * size_of::<State>(),
+        }
+    }
+}
+
+/// Transition table sizes in bytes: full 256 entry rows, rows indexed by byte class, and
+/// packed rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionReport {
+    pub states: usize,
+    pub class_count: usize,
+    pub full_bytes: usize,
+    pub classes_bytes: usize,
+    pub packed_bytes: usize,
+}
+
+impl fmt::Display for CompressionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "{} states, {} byte classes: {} bytes as full rows, {} bytes by class, {} bytes packed ({:.1}%)",
+            self.states, self.class_count, self.full_bytes, self.classes_bytes, self.packed_bytes,
+            100.0 * self.packed_bytes as f64 / self.full_bytes as f64
+        )
+    }
+}
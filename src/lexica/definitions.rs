@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::lexica::{
+    parse::{parse_with_names, RegexParseError},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionError {
+    Undefined(String),
+    /// The chain of names that refer back to the first one, e.g. `["A", "B", "A"]`.
+    Cycle(Vec<String>),
+    /// A reference to a definition with a trailing context, which is only allowed at the
+    /// top of a token pattern.
+    TrailingContext(String),
+    /// A pattern failed to parse, `name` is the definition it belongs to if any.
+    Parse { name: Option<String>, error: RegexParseError },
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::Undefined(name) => write!(f, "undefined name '{}'", name),
+            DefinitionError::Cycle(names) => write!(f, "cyclic definition {}", names.join(" -> ")),
+            DefinitionError::TrailingContext(name) => write!(f, "definition '{}' has a trailing context and can't be referenced", name),
+            DefinitionError::Parse { name: Some(name), error } => write!(f, "in definition '{}': {}", name, error),
+            DefinitionError::Parse { name: None, error } => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+#[derive(Debug, Clone)]
+enum Definition {
+    Regex(Regex),
+    Pattern(String),
+}
+
+/// Named regex fragments, referenced as `{NAME}` from textual patterns like the
+/// definitions section of a lex file.
+#[derive(Debug, Clone, Default)]
+pub struct RegexDefinitions {
+    definitions: HashMap<String, Definition>,
+}
+
+impl RegexDefinitions {
+    pub fn new() -> Self {
+        RegexDefinitions { definitions: HashMap::new() }
+    }
+
+    pub fn define(&mut self, name: &str, regex: Regex) {
+        self.definitions.insert(name.to_string(), Definition::Regex(regex));
+    }
+
+    /// Registers a textual pattern, its references are resolved when it is used so
+    /// definitions may appear in any order.
+    pub fn define_pattern(&mut self, name: &str, pattern: &str) {
+        self.definitions.insert(name.to_string(), Definition::Pattern(pattern.to_string()));
+    }
+
+    pub fn get(&self, name: &str) -> Result<Regex, DefinitionError> {
+        self.resolve(name, &mut Vec::new())
+    }
+
+    pub fn parse(&self, pattern: &str) -> Result<Regex, DefinitionError> {
+        self.expand(pattern, None, &mut Vec::new())
+    }
+
+    /// Resolves every definition, reporting the first undefined name, cycle or invalid pattern.
+    pub fn check(&self) -> Result<(), DefinitionError> {
+        let mut names: Vec<&String> = self.definitions.keys().collect();
+        names.sort();
+        for name in names {
+            self.get(name)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the pattern of every token, ready to be given to `TokensDFA::new`.
+    pub fn tokens<T>(&self, tokens_patterns: Vec<(T, TokenUses, &str)>) -> Result<Vec<(T, TokenUses, Regex)>, DefinitionError>
+        where T: Eq + Copy + Hash {
+        tokens_patterns.into_iter()
+            .map(|(name, mask, pattern)| Ok((name, mask, self.parse(pattern)?)))
+            .collect()
+    }
+
+    fn resolve(&self, name: &str, stack: &mut Vec<String>) -> Result<Regex, DefinitionError> {
+        if let Some(index) = stack.iter().position(|other| other == name) {
+            let mut cycle = stack[index..].to_vec();
+            cycle.push(name.to_string());
+            return Err(DefinitionError::Cycle(cycle));
+        }
+        match self.definitions.get(name) {
+            None => Err(DefinitionError::Undefined(name.to_string())),
+            Some(Definition::Regex(regex)) => Ok(regex.clone()),
+            Some(Definition::Pattern(pattern)) => {
+                stack.push(name.to_string());
+                let regex = self.expand(pattern, Some(name), stack);
+                stack.pop();
+                regex
+            },
+        }
+    }
+
+    fn expand(&self, pattern: &str, owner: Option<&str>, stack: &mut Vec<String>) -> Result<Regex, DefinitionError> {
+        let mut failure = None;
+        let regex = parse_with_names(pattern, &mut |name| match self.resolve(name, stack) {
+            Ok(regex) if regex.has_trailing_context() => {
+                failure = Some(DefinitionError::TrailingContext(name.to_string()));
+                None
+            },
+            Ok(regex) => Some(regex),
+            Err(error) => {
+                failure = Some(error);
+                None
+            }
+        });
+        match (regex, failure) {
+            (_, Some(error)) => Err(error),
+            (Ok(regex), None) => Ok(regex),
+            (Err(error), None) => Err(DefinitionError::Parse { name: owner.map(str::to_string), error }),
+        }
+    }
+}
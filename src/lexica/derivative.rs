@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{State, Table, ERROR_STATE},
+    class::CharClass,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+impl Regex {
+    /// Brzozowski derivative: the strings `w` such that `byte` followed by `w` is matched.
+    pub fn derivative(&self, byte: u8) -> Regex {
+        match self {
+            Regex::Concat(regexs) => {
+                let Some((first, rest)) = regexs.split_first() else {
+                    return Regex::nothing();
+                };
+                let rest = Regex::Concat(rest.to_vec());
+                let consumed = Regex::Concat(vec![first.derivative(byte), rest.clone()]);
+                if first.is_nullable() {
+                    return Regex::Union(vec![consumed, rest.derivative(byte)]);
+                }
+                consumed
+            },
+            Regex::Union(regexs) => Regex::Union(regexs.iter().map(|re| re.derivative(byte)).collect()),
+            Regex::Repeat(re) => Regex::Concat(vec![re.derivative(byte), Regex::Repeat(re.clone())]),
+            Regex::Bounded(re, min, max) => {
+                if max.is_some_and(|max| max == 0 || max < *min) {
+                    return Regex::nothing();
+                }
+                let rest = Regex::Bounded(re.clone(), min.saturating_sub(1), max.map(|max| max - 1));
+                Regex::Concat(vec![re.derivative(byte), rest])
+            },
+            Regex::Intersection(regexs) => Regex::Intersection(regexs.iter().map(|re| re.derivative(byte)).collect()),
+            Regex::Complement(re) => Regex::Complement(Box::new(re.derivative(byte))),
+            Regex::Trailing(head, tail) => Regex::Concat(vec![(**head).clone(), (**tail).clone()]).derivative(byte),
+            Regex::Capture(_, re) => re.derivative(byte),
+            Regex::Word(word) => match word.split_first() {
+                Some((first, rest)) if *first == byte => Regex::Word(rest.to_vec()),
+                _ => Regex::nothing(),
+            },
+            Regex::Char(c) if *c == byte => Regex::Empty,
+            Regex::Class(class) if class.contains(byte) => Regex::Empty,
+            Regex::Char(_) | Regex::Class(_) | Regex::Empty => Regex::nothing(),
+        }
+    }
+
+    /// Simplifies the regex, drops capture names and sorts every union and intersection, so that derivatives of equivalent
+    /// expressions end up structurally equal.
+    pub fn canonical(self) -> Regex {
+        sort_unions(self.simplify())
+    }
+
+    /// Classes of bytes that may start a match, every byte of a class has the same derivative.
+    fn leading_classes(&self, out: &mut Vec<CharClass>) {
+        match self {
+            Regex::Concat(regexs) => {
+                for re in regexs {
+                    re.leading_classes(out);
+                    if !re.is_nullable() {
+                        break;
+                    }
+                }
+            },
+            Regex::Union(regexs) | Regex::Intersection(regexs) => {
+                for re in regexs {
+                    re.leading_classes(out);
+                }
+            },
+            Regex::Repeat(re) | Regex::Bounded(re, _, _) | Regex::Complement(re) | Regex::Capture(_, re) => re.leading_classes(out),
+            Regex::Trailing(head, tail) => {
+                head.leading_classes(out);
+                if head.is_nullable() {
+                    tail.leading_classes(out);
+                }
+            },
+            Regex::Word(word) => {
+                if let Some(first) = word.first() {
+                    out.push(CharClass::from_bytes(&[*first]));
+                }
+            },
+            Regex::Char(c) => out.push(CharClass::from_bytes(&[*c])),
+            Regex::Class(class) => out.push(class.clone()),
+            Regex::Empty => {},
+        }
+    }
+}
+
+fn sort_unions(regex: Regex) -> Regex {
+    match regex {
+        Regex::Union(regexs) => {
+            let mut regexs: Vec<Regex> = regexs.into_iter().map(sort_unions).collect();
+            regexs.sort();
+            regexs.dedup();
+            Regex::Union(regexs)
+        },
+        Regex::Intersection(regexs) => {
+            let mut regexs: Vec<Regex> = regexs.into_iter().map(sort_unions).collect();
+            regexs.sort();
+            regexs.dedup();
+            Regex::Intersection(regexs)
+        },
+        Regex::Complement(re) => Regex::Complement(Box::new(sort_unions(*re))),
+        Regex::Trailing(head, tail) => Regex::Trailing(Box::new(sort_unions(*head)), Box::new(sort_unions(*tail))),
+        Regex::Capture(_, re) => sort_unions(*re),
+        Regex::Concat(regexs) => Regex::Concat(regexs.into_iter().map(sort_unions).collect()),
+        Regex::Repeat(re) => Regex::Repeat(Box::new(sort_unions(*re))),
+        Regex::Bounded(re, min, max) => Regex::Bounded(Box::new(sort_unions(*re)), min, max),
+        regex => regex,
+    }
+}
+
+/// Splits the 256 bytes into groups that behave the same for every regex in `regexs`,
+/// returning one representative byte and the members of each group.
+fn byte_partition(regexs: &[Regex]) -> Vec<(u8, CharClass)> {
+    let mut classes = Vec::new();
+    for re in regexs {
+        re.leading_classes(&mut classes);
+    }
+    let mut groups: Vec<CharClass> = vec![CharClass::full()];
+    for class in classes {
+        let mut refined = Vec::with_capacity(groups.len());
+        for group in groups {
+            let inside = group.intersection(&class);
+            let outside = group.intersection(&class.negate());
+            if !inside.is_empty() {
+                refined.push(inside);
+            }
+            if !outside.is_empty() {
+                refined.push(outside);
+            }
+        }
+        groups = refined;
+    }
+    groups.into_iter().map(|group| {
+        let representative = group.bytes().next().unwrap();
+        (representative, group)
+    }).collect()
+}
+
+/// Builds the transition table and finals of a DFA whose states are the vectors of
+/// derivatives of every token regex. The first nullable token of a state is accepted.
+pub(crate) fn dfa_from_derivatives<T>(tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Table<T>
+    where T: Eq + Copy + Hash {
+    let tokens: Vec<(T, TokenUses)> = tokens_regexs.iter().map(|(name, mask, _)| (*name, *mask)).collect();
+    let initial: Vec<Regex> = tokens_regexs.into_iter().map(|(_, _, regex)| regex.canonical()).collect();
+
+    let mut table: Vec<[State; 256]> = Vec::new();
+    let mut finals: Vec<(T, TokenUses, State)> = Vec::new();
+    let mut states: Vec<Vec<Regex>> = Vec::new();
+    let mut indexes: HashMap<Vec<Regex>, State> = HashMap::new();
+
+    indexes.insert(initial.clone(), 0);
+    states.push(initial);
+
+    let mut current = 0;
+    while current < states.len() {
+        let regexs = states[current].clone();
+        let mut row = [ERROR_STATE; 256];
+
+        if let Some(index) = regexs.iter().position(Regex::is_nullable) {
+            let (name, mask) = tokens[index];
+            finals.push((name, mask, current as State));
+        }
+
+        for (byte, group) in byte_partition(&regexs) {
+            let next: Vec<Regex> = regexs.iter().map(|re| re.derivative(byte).canonical()).collect();
+            if next.iter().all(Regex::is_nothing) {
+                continue;
+            }
+            let state = match indexes.get(&next) {
+                Some(state) => *state,
+                None => {
+                    let state = states.len() as State;
+                    indexes.insert(next.clone(), state);
+                    states.push(next);
+                    state
+                }
+            };
+            for member in group.bytes() {
+                row[member as usize] = state;
+            }
+        }
+        table.push(row);
+        current += 1;
+    }
+    drop_dead_states((table, finals))
+}
+
+/// Removes the states from which no final state can be reached, their edges go to
+/// `ERROR_STATE` instead so the automaton stops as soon as a match is impossible.
+/// The initial state is always kept.
+fn drop_dead_states<T>((table, finals): Table<T>) -> Table<T> where T: Copy {
+    let mut previous: Vec<Vec<usize>> = vec![Vec::new(); table.len()];
+    for (state, row) in table.iter().enumerate() {
+        for next in row.iter().filter(|next| **next != ERROR_STATE) {
+            previous[*next as usize].push(state);
+        }
+    }
+    let mut live = vec![false; table.len()];
+    let mut pending: Vec<usize> = finals.iter().map(|(_, _, state)| *state as usize).collect();
+    while let Some(state) = pending.pop() {
+        if !live[state] {
+            live[state] = true;
+            pending.extend(&previous[state]);
+        }
+    }
+    live[0] = true;
+
+    let mut renumbered = vec![ERROR_STATE; table.len()];
+    let mut kept = 0;
+    for (state, is_live) in live.iter().enumerate() {
+        if *is_live {
+            renumbered[state] = kept;
+            kept += 1;
+        }
+    }
+    let table = table.iter().zip(&live)
+        .filter(|(_, is_live)| **is_live)
+        .map(|(row, _)| row.map(|next| if next == ERROR_STATE { ERROR_STATE } else { renumbered[next as usize] }))
+        .collect();
+    let finals = finals.into_iter().map(|(name, mask, state)| (name, mask, renumbered[state as usize])).collect();
+    (table, finals)
+}
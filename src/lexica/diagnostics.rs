@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{State, TokensNFA, ERROR_STATE, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// Something about a token spec that is legal but probably not intended. States are numbered
+/// as in the DFA of `Construction::Subset`, or as in the DFA returned by
+/// `DFABuilder::build_with_warnings`, and come with the shortest input reaching them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexerSpecWarning<T> {
+    /// A state accepts several tokens, `tokens` are in declaration order and the first one,
+    /// `winner`, is the one produced since ties go to the earliest declaration.
+    Conflict { state: State, example: Vec<u8>, tokens: Vec<T>, winner: T },
+    /// Every input the token accepts is won by an earlier declaration, listed in `by`.
+    Shadowed { token: T, example: Vec<u8>, by: Vec<T> },
+}
+
+impl<T> fmt::Display for LexerSpecWarning<T> where T: Debug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexerSpecWarning::Conflict { state, example, tokens, winner } => write!(
+                f, "state {} (reached by {:?}) accepts {:?}, {:?} wins as it is declared first",
+                state, String::from_utf8_lossy(example), tokens, winner
+            ),
+            LexerSpecWarning::Shadowed { token, example, by } => write!(
+                f, "{:?} is never produced, its inputs (like {:?}) are taken by {:?} declared before it",
+                token, String::from_utf8_lossy(example), by
+            ),
+        }
+    }
+}
+
+/// Conflicts between the tokens of a spec, followed by the tokens they fully shadow.
+pub fn spec_warnings<T>(tokens_regexs: &[(T, TokenUses, Regex)]) -> Vec<LexerSpecWarning<T>>
+    where T: Eq + Copy + Hash {
+    let nfa = TokensNFA::new(tokens_regexs.to_vec());
+    let (table, subsets) = nfa.subsets();
+    let examples = shortest_inputs(&table);
+
+    let mut warnings = Vec::new();
+    // For each declaration, the declarations winning the states it is accepted in.
+    let mut winners: Vec<Vec<usize>> = vec![Vec::new(); tokens_regexs.len()];
+    for (state, subset) in subsets.iter().enumerate() {
+        let mut accepted: Vec<usize> = Vec::new();
+        for (index, (name, mask, final_state)) in nfa.finals.iter().enumerate() {
+            let duplicate = accepted.iter().any(|other| nfa.finals[*other].0 == *name && nfa.finals[*other].1 == *mask);
+            if subset.contains(final_state) && !duplicate {
+                accepted.push(index);
+            }
+        }
+        let Some(winner) = accepted.first().copied() else { continue };
+        for index in &accepted {
+            winners[*index].push(winner);
+        }
+        if accepted.len() > 1 {
+            warnings.push(LexerSpecWarning::Conflict {
+                state: state as State,
+                example: examples[state].clone(),
+                tokens: accepted.iter().map(|index| nfa.finals[*index].0).collect(),
+                winner: nfa.finals[winner].0,
+            });
+        }
+    }
+
+    for (index, (name, _, _)) in tokens_regexs.iter().enumerate() {
+        if winners[index].is_empty() || winners[index].contains(&index) {
+            continue;
+        }
+        let mut by: Vec<T> = Vec::new();
+        for winner in &winners[index] {
+            if !by.contains(&tokens_regexs[*winner].0) {
+                by.push(tokens_regexs[*winner].0);
+            }
+        }
+        let state = subsets.iter().position(|subset| subset.contains(&nfa.finals[index].2)).unwrap();
+        warnings.push(LexerSpecWarning::Shadowed { token: *name, example: examples[state].clone(), by });
+    }
+    warnings
+}
+
+/// Shortest input reaching each state, the smallest one among those of the same length.
+fn shortest_inputs(table: &[[State; 256]]) -> Vec<Vec<u8>> {
+    let mut inputs: Vec<Option<Vec<u8>>> = vec![None; table.len()];
+    inputs[INIT_STATE as usize] = Some(Vec::new());
+    let mut queue = VecDeque::from([INIT_STATE]);
+    while let Some(state) = queue.pop_front() {
+        for (char, next) in table[state as usize].iter().enumerate() {
+            if *next != ERROR_STATE && inputs[*next as usize].is_none() {
+                let mut input = inputs[state as usize].clone().unwrap();
+                input.push(char as u8);
+                inputs[*next as usize] = Some(input);
+                queue.push_back(*next);
+            }
+        }
+    }
+    inputs.into_iter().map(Option::unwrap_or_default).collect()
+}
@@ -0,0 +1,200 @@
+use std::fmt::{self, Display, Write};
+
+use crate::lexica::{class::CharClass, regex::Regex};
+
+const UNION: u8 = 0;
+const INTERSECTION: u8 = 1;
+const CONCAT: u8 = 2;
+const POSTFIX: u8 = 3;
+
+impl Display for Regex {
+    /// Renders the regex in the syntax accepted by `Regex::parse`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Trailing context is only meaningful at the top, anywhere else it is written as a concatenation.
+        match self {
+            Regex::Trailing(head, tail) => {
+                write_regex(f, head, UNION)?;
+                f.write_char('/')?;
+                write_regex(f, tail, UNION)
+            },
+            Regex::Union(regexs) | Regex::Concat(regexs) | Regex::Intersection(regexs) if regexs.len() == 1 => regexs[0].fmt(f),
+            _ => write_regex(f, self, UNION),
+        }
+    }
+}
+
+impl Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == CharClass::from_bytes(b"\n").negate() {
+            return f.write_char('.');
+        }
+        let negated = self.negate();
+        let use_negated = !self.is_empty() && !negated.is_empty() && negated.ranges().count() < self.ranges().count();
+        let class = if use_negated { &negated } else { self };
+
+        f.write_char('[')?;
+        if use_negated || self.is_empty() {
+            f.write_char('^')?;
+        }
+        if self.is_empty() {
+            f.write_str("\\x00-\\xFF")?;
+        }
+        for range in class.ranges() {
+            let (low, high) = (*range.start(), *range.end());
+            write_class_byte(f, low)?;
+            if high > low + 1 {
+                f.write_char('-')?;
+            }
+            if high > low {
+                write_class_byte(f, high)?;
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+fn write_regex(f: &mut fmt::Formatter<'_>, regex: &Regex, level: u8) -> fmt::Result {
+    if let Regex::Union(regexs) | Regex::Concat(regexs) | Regex::Intersection(regexs) = regex {
+        if regexs.len() == 1 {
+            return write_regex(f, &regexs[0], level);
+        }
+    }
+    if let Regex::Trailing(head, tail) = regex {
+        return write_regex(f, &Regex::Concat(vec![(**head).clone(), (**tail).clone()]), level);
+    }
+    if let Regex::Bounded(_, min, Some(max)) = regex {
+        if max < min {
+            // Inverted bounds match nothing and have no syntax of their own.
+            return write_regex(f, &Regex::nothing(), level);
+        }
+    }
+    let precedence = precedence(regex);
+    if precedence < level {
+        f.write_char('(')?;
+    }
+    match regex {
+        Regex::Trailing(..) => unreachable!(),
+        Regex::Union(regexs) if regexs.is_empty() => write!(f, "{}", CharClass::new())?,
+        Regex::Union(regexs) => {
+            for (index, re) in regexs.iter().enumerate() {
+                if index > 0 {
+                    f.write_char('|')?;
+                }
+                if *re != Regex::Empty {
+                    write_regex(f, re, INTERSECTION)?;
+                }
+            }
+        },
+        Regex::Intersection(regexs) if regexs.is_empty() => write!(f, "~{}", CharClass::new())?,
+        Regex::Intersection(regexs) => {
+            for (index, re) in regexs.iter().enumerate() {
+                if index > 0 {
+                    f.write_char('&')?;
+                }
+                write_regex(f, re, CONCAT)?;
+            }
+        },
+        Regex::Capture(name, re) => {
+            write!(f, "(?<{}>", name)?;
+            write_regex(f, re, UNION)?;
+            f.write_char(')')?;
+        },
+        Regex::Complement(re) => {
+            f.write_char('~')?;
+            write_regex(f, re, POSTFIX)?;
+        },
+        Regex::Concat(regexs) if regexs.is_empty() => write_regex(f, &Regex::Empty, level)?,
+        Regex::Concat(regexs) => {
+            for re in regexs {
+                write_regex(f, re, CONCAT)?;
+            }
+        },
+        Regex::Repeat(re) => {
+            write_regex(f, re, POSTFIX + 1)?;
+            f.write_char('*')?;
+        },
+        Regex::Bounded(re, min, max) => {
+            write_regex(f, re, POSTFIX + 1)?;
+            match (min, max) {
+                (1, None) => f.write_char('+')?,
+                (0, Some(1)) => f.write_char('?')?,
+                (min, None) => write!(f, "{{{},}}", min)?,
+                (min, Some(max)) if min == max => write!(f, "{{{}}}", min)?,
+                (min, Some(max)) => write!(f, "{{{},{}}}", min, max)?,
+            }
+        },
+        Regex::Word(word) => write_bytes(f, word)?,
+        Regex::Char(c) => write_bytes(f, &[*c])?,
+        Regex::Class(class) => write!(f, "{}", class)?,
+        Regex::Empty => {
+            if level > UNION {
+                f.write_str("()")?;
+            }
+        },
+    }
+    if precedence < level {
+        f.write_char(')')?;
+    }
+    Ok(())
+}
+
+fn precedence(regex: &Regex) -> u8 {
+    match regex {
+        Regex::Union(regexs) if !regexs.is_empty() => UNION,
+        Regex::Trailing(..) => UNION,
+        Regex::Intersection(regexs) if !regexs.is_empty() => INTERSECTION,
+        Regex::Concat(regexs) if !regexs.is_empty() => CONCAT,
+        Regex::Union(_) | Regex::Intersection(_) | Regex::Concat(_) => POSTFIX + 1,
+        Regex::Repeat(_) | Regex::Bounded(..) | Regex::Complement(_) => POSTFIX,
+        Regex::Word(word) => match std::str::from_utf8(word) {
+            Ok(text) if text.chars().count() == 1 => POSTFIX + 1,
+            _ if word.len() == 1 => POSTFIX + 1,
+            _ => CONCAT,
+        },
+        Regex::Char(_) | Regex::Class(_) | Regex::Capture(..) | Regex::Empty => POSTFIX + 1,
+    }
+}
+
+fn write_bytes(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        for c in text.chars() {
+            if c.is_ascii() {
+                write_byte(f, c as u8)?;
+            } else {
+                f.write_char(c)?;
+            }
+        }
+        return Ok(());
+    }
+    for byte in bytes {
+        write_byte(f, *byte)?;
+    }
+    Ok(())
+}
+
+fn write_byte(f: &mut fmt::Formatter<'_>, byte: u8) -> fmt::Result {
+    match byte {
+        b'\\' | b'.' | b'[' | b']' | b'(' | b')' | b'{' | b'}' | b'|' | b'&' | b'~' | b'*' | b'+' | b'?' | b'^' | b'$' | b'/' => {
+            write!(f, "\\{}", byte as char)
+        },
+        _ => write_escaped(f, byte),
+    }
+}
+
+fn write_class_byte(f: &mut fmt::Formatter<'_>, byte: u8) -> fmt::Result {
+    match byte {
+        b'\\' | b']' | b'[' | b'^' | b'-' => write!(f, "\\{}", byte as char),
+        _ => write_escaped(f, byte),
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, byte: u8) -> fmt::Result {
+    match byte {
+        b'\n' => f.write_str("\\n"),
+        b'\r' => f.write_str("\\r"),
+        b'\t' => f.write_str("\\t"),
+        b'\0' => f.write_str("\\0"),
+        b' '..=b'~' => f.write_char(byte as char),
+        _ => write!(f, "\\x{:02X}", byte),
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::{Debug, Write};
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{State, TokensDFA, TokensNFA, ERROR_STATE, INIT_STATE},
+    class::CharClass
+};
+
+impl<T> TokensNFA<T> where T: Eq + Copy + Hash + Debug {
+    /// Renders the automaton in Graphviz dot syntax.
+    pub fn to_dot(&self) -> String {
+        self.render((0..self.states).collect(), INIT_STATE)
+    }
+
+    /// Renders only the states that can still be reached after reading `prefix`.
+    pub fn to_dot_from_prefix(&self, prefix: &[u8]) -> String {
+        let mut current = self.epsilon_closure(vec![INIT_STATE]);
+        for char in prefix {
+            current = self.epsilon_closure(self.step(&current, *char));
+        }
+        let edges = self.edges();
+        let states = reachable(current.clone(), |state| edges.get(&state).map(|targets| targets.keys().copied().collect()).unwrap_or_default());
+        self.render_with(states, current, &edges)
+    }
+
+    fn render(&self, states: BTreeSet<State>, start: State) -> String {
+        self.render_with(states, vec![start], &self.edges())
+    }
+
+    fn render_with(&self, states: BTreeSet<State>, starts: Vec<State>, edges: &Edges) -> String {
+        let mut accepting: HashMap<State, Vec<String>> = HashMap::new();
+        for (name, mask, state) in &self.finals {
+            accepting.entry(*state).or_default().push(format!("{:?} ({:?})", name, mask));
+        }
+        let mut dot = Graph::new("TokensNFA", &starts);
+        for state in &states {
+            dot.node(*state, accepting.get(state).map(Vec::as_slice).unwrap_or_default());
+        }
+        for state in &states {
+            if let Some(targets) = edges.get(state) {
+                for (target, (bytes, epsilon)) in targets {
+                    if *epsilon {
+                        dot.edge(*state, *target, "ε".to_string());
+                    }
+                    if !bytes.is_empty() {
+                        dot.edge(*state, *target, byte_label(bytes));
+                    }
+                }
+            }
+        }
+        dot.finish()
+    }
+
+    /// Edges grouped by source and target, with the bytes read and whether an epsilon move links them.
+    fn edges(&self) -> Edges {
+        let mut edges: Edges = BTreeMap::new();
+        for (from, targets) in &self.transitions {
+            for (range, to) in targets {
+                let (class, _) = edges.entry(*from).or_default().entry(*to).or_default();
+                *class = class.union(&CharClass::from_range(range.clone()));
+            }
+        }
+        for (from, targets) in &self.empty_transitions {
+            for to in targets {
+                edges.entry(*from).or_default().entry(*to).or_default().1 = true;
+            }
+        }
+        edges
+    }
+}
+
+type Edges = BTreeMap<State, BTreeMap<State, (CharClass, bool)>>;
+
+impl<T> TokensDFA<T> where T: Eq + Copy + Hash + Debug {
+    /// Renders the automaton in Graphviz dot syntax, the error state is left out.
+    pub fn to_dot(&self) -> String {
+        self.render((0..self.states as State).collect(), INIT_STATE)
+    }
+
+    /// Renders only the states that can still be reached after reading `prefix`.
+    pub fn to_dot_from_prefix(&self, prefix: &[u8]) -> String {
+        let mut state = INIT_STATE;
+        for char in prefix {
+            if state == ERROR_STATE {
+                break;
+            }
+            state = self.next(state, *char);
+        }
+        if state == ERROR_STATE {
+            return Graph::new("TokensDFA", &[]).finish();
+        }
+        let states = reachable(vec![state], |state| {
+            (0..self.class_count).map(|class| self.next_by_class(state, class)).filter(|next| *next != ERROR_STATE).collect()
+        });
+        self.render(states, state)
+    }
+
+    fn render(&self, states: BTreeSet<State>, start: State) -> String {
+        let mut dot = Graph::new("TokensDFA", &[start]);
+        for state in &states {
+            let tokens = match &self.finals[*state as usize] {
+                (Some(name), mask) => vec![format!("{:?} ({:?})", name, mask)],
+                (None, _) => Vec::new(),
+            };
+            dot.node(*state, &tokens);
+        }
+        for state in &states {
+            let mut targets: BTreeMap<State, Vec<u8>> = BTreeMap::new();
+            for char in 0..=255 {
+                let next = self.next(*state, char);
+                if next != ERROR_STATE {
+                    targets.entry(next).or_default().push(char);
+                }
+            }
+            for (target, bytes) in targets {
+                dot.edge(*state, target, byte_label(&CharClass::from_bytes(&bytes)));
+            }
+        }
+        dot.finish()
+    }
+}
+
+fn reachable<F>(starts: Vec<State>, nexts: F) -> BTreeSet<State>
+    where F: Fn(State) -> Vec<State> {
+    let mut seen: BTreeSet<State> = starts.iter().copied().collect();
+    let mut queue: VecDeque<State> = starts.into();
+    while let Some(state) = queue.pop_front() {
+        for next in nexts(state) {
+            if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+/// Class contents in regex notation without the brackets, e.g. `a-z`, `0-9_` or `^\n`.
+fn byte_label(class: &CharClass) -> String {
+    let text = class.to_string();
+    match text.strip_prefix('[').and_then(|inner| inner.strip_suffix(']')) {
+        Some(inner) => inner.to_string(),
+        None => text,
+    }
+}
+
+struct Graph {
+    out: String,
+}
+
+impl Graph {
+    fn new(name: &str, starts: &[State]) -> Self {
+        let mut out = format!("digraph {} {{\n    rankdir=LR;\n    node [shape=circle];\n", name);
+        for start in starts {
+            let _ = writeln!(out, "    start{} [shape=point];\n    start{} -> {};", start, start, start);
+        }
+        Graph { out }
+    }
+
+    /// Accepting states are drawn with a double circle and list their tokens below the number.
+    fn node(&mut self, state: State, tokens: &[String]) {
+        if tokens.is_empty() {
+            let _ = writeln!(self.out, "    {};", state);
+            return;
+        }
+        let mut label = state.to_string();
+        for token in tokens {
+            label.push_str("\\n");
+            label.push_str(&escape(token));
+        }
+        let _ = writeln!(self.out, "    {} [shape=doublecircle, label=\"{}\"];", state, label);
+    }
+
+    fn edge(&mut self, from: State, to: State, label: String) {
+        let _ = writeln!(self.out, "    {} -> {} [label=\"{}\"];", from, to, escape(&label));
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push_str("}\n");
+        self.out
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use crate::lexica::{
+    automata::{Construction, DFABuilder, State, TokensDFA, ERROR_STATE, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// A shortest input on which two regexs disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub input: Vec<u8>,
+    /// Whether the first regex accepts `input`; the second one does the opposite.
+    pub accepted_by_first: bool,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = if self.accepted_by_first { "first" } else { "second" };
+        write!(f, "{:?} is only accepted by the {} regex", String::from_utf8_lossy(&self.input), side)
+    }
+}
+
+impl Regex {
+    /// Checks that both regexs accept exactly the same strings.
+    pub fn equivalent(first: &Regex, second: &Regex) -> Result<(), Counterexample> {
+        match find_difference(first, second, |first, second| first != second) {
+            Some(counterexample) => Err(counterexample),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that every string accepted by `first` is also accepted by `second`.
+    pub fn is_subset(first: &Regex, second: &Regex) -> Result<(), Counterexample> {
+        match find_difference(first, second, |first, second| first && !second) {
+            Some(counterexample) => Err(counterexample),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A state of the product automaton, one state of each DFA.
+type Pair = (State, State);
+
+fn single_token_dfa(regex: &Regex) -> TokensDFA<()> {
+    DFABuilder::new()
+        .construction(Construction::Derivatives)
+        .build(vec![((), TokenUses::Default, regex.clone())])
+}
+
+fn accepts(dfa: &TokensDFA<()>, state: State) -> bool {
+    state != ERROR_STATE && dfa.finals[state as usize].0.is_some()
+}
+
+fn step(dfa: &TokensDFA<()>, state: State, byte: u8) -> State {
+    if state == ERROR_STATE {
+        return ERROR_STATE;
+    }
+    dfa.next(state, byte)
+}
+
+/// Breadth first search over the product automaton, so the first state pair where
+/// `differs` holds is reached through a shortest input.
+fn find_difference<F>(first: &Regex, second: &Regex, differs: F) -> Option<Counterexample>
+    where F: Fn(bool, bool) -> bool {
+    let first = single_token_dfa(first);
+    let second = single_token_dfa(second);
+
+    let start = (INIT_STATE, INIT_STATE);
+    // Each reached pair with the pair and byte it was first reached from.
+    let mut parents: HashMap<Pair, Option<(Pair, u8)>> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    parents.insert(start, None);
+
+    while let Some(pair) = queue.pop_front() {
+        let (accepted_by_first, accepted_by_second) = (accepts(&first, pair.0), accepts(&second, pair.1));
+        if differs(accepted_by_first, accepted_by_second) {
+            let mut input = Vec::new();
+            let mut current = pair;
+            while let Some((parent, byte)) = parents[&current] {
+                input.push(byte);
+                current = parent;
+            }
+            input.reverse();
+            return Some(Counterexample { input, accepted_by_first });
+        }
+
+        for byte in 0..=255 {
+            let next = (step(&first, pair.0, byte), step(&second, pair.1, byte));
+            if next == (ERROR_STATE, ERROR_STATE) || parents.contains_key(&next) {
+                continue;
+            }
+            parents.insert(next, Some((pair, byte)));
+            queue.push_back(next);
+        }
+    }
+    None
+}
@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use crate::lexica::{
+    automata::{Construction, DFABuilder, State, TokensDFA, ERROR_STATE, INIT_STATE},
+    class::CharClass,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// Seeded generator of random strings accepted by a regex, the same seed always yields
+/// the same strings.
+pub struct RegexGenerator {
+    state: u64,
+    max_repeat: u32,
+    alphabet: CharClass,
+}
+
+impl RegexGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed, max_repeat: 4, alphabet: CharClass::full() }
+    }
+
+    /// Most iterations added on top of the minimum of `*`, `+` and `{m,n}`.
+    pub fn max_repeat(mut self, max_repeat: u32) -> Self {
+        self.max_repeat = max_repeat;
+        self
+    }
+
+    /// Restricts the generated bytes, e.g. to printable text for a lexer reading UTF-8.
+    pub fn alphabet(mut self, alphabet: CharClass) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// A random string matched by `regex`, or `None` when no string within the bounds
+    /// and alphabet exists.
+    pub fn generate(&mut self, regex: &Regex) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        if !self.push(regex, &mut out) {
+            return None;
+        }
+        Some(out)
+    }
+
+    fn push(&mut self, regex: &Regex, out: &mut Vec<u8>) -> bool {
+        match regex {
+            Regex::Concat(regexs) => regexs.iter().all(|re| self.push(re, out)),
+            Regex::Union(regexs) => {
+                let mut branches: Vec<&Regex> = regexs.iter().collect();
+                while !branches.is_empty() {
+                    let branch = branches.swap_remove(self.below(branches.len()));
+                    let len = out.len();
+                    if self.push(branch, out) {
+                        return true;
+                    }
+                    out.truncate(len);
+                }
+                false
+            },
+            Regex::Repeat(re) => self.repeat(re, 0, None, out),
+            Regex::Bounded(re, min, max) => self.repeat(re, *min, *max, out),
+            Regex::Trailing(head, tail) => self.push(head, out) && self.push(tail, out),
+            Regex::Capture(_, re) => self.push(re, out),
+            Regex::Intersection(_) | Regex::Complement(_) => self.walk(regex, out),
+            Regex::Word(word) => {
+                out.extend(word);
+                word.iter().all(|byte| self.alphabet.contains(*byte))
+            },
+            Regex::Char(c) => {
+                out.push(*c);
+                self.alphabet.contains(*c)
+            },
+            Regex::Class(class) => {
+                let class = class.intersection(&self.alphabet);
+                if class.is_empty() {
+                    return false;
+                }
+                let index = self.below(class.len());
+                out.push(class.bytes().nth(index).unwrap());
+                true
+            },
+            Regex::Empty => true,
+        }
+    }
+
+    fn repeat(&mut self, re: &Regex, min: u32, max: Option<u32>, out: &mut Vec<u8>) -> bool {
+        if max.is_some_and(|max| max < min) {
+            return false;
+        }
+        let limit = min.saturating_add(self.max_repeat);
+        let max = max.map_or(limit, |max| max.min(limit));
+        let count = min + self.below((max - min) as usize + 1) as u32;
+        for iteration in 0..count {
+            let len = out.len();
+            if !self.push(re, out) {
+                // Extra iterations are optional, stop at the ones that succeeded.
+                out.truncate(len);
+                return iteration >= min;
+            }
+        }
+        true
+    }
+
+    /// Intersections and complements have no direct structure to follow, so a random walk
+    /// over their DFA is made, finished by the shortest path to an accepting state.
+    fn walk(&mut self, regex: &Regex, out: &mut Vec<u8>) -> bool {
+        let dfa = single_token_dfa(regex);
+        let live = live_states(&dfa, &self.alphabet);
+        if !live[INIT_STATE as usize] {
+            return false;
+        }
+        let mut state = INIT_STATE;
+        for _ in 0..self.below(2 * self.max_repeat as usize + 1) {
+            let nexts: Vec<u8> = self.alphabet.bytes()
+                .filter(|byte| {
+                    let next = dfa.next(state, *byte);
+                    next != ERROR_STATE && live[next as usize]
+                })
+                .collect();
+            if nexts.is_empty() {
+                break;
+            }
+            let byte = nexts[self.below(nexts.len())];
+            out.push(byte);
+            state = dfa.next(state, byte);
+        }
+        match shortest_path(&dfa, state, &self.alphabet) {
+            Some(rest) => {
+                out.extend(rest);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// splitmix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+impl Regex {
+    /// The shortest string matched by the regex, the smallest byte wise among those of the
+    /// same length, or `None` if it matches nothing.
+    pub fn shortest_example(&self) -> Option<Vec<u8>> {
+        shortest_path(&single_token_dfa(self), INIT_STATE, &CharClass::full())
+    }
+}
+
+fn single_token_dfa(regex: &Regex) -> TokensDFA<()> {
+    DFABuilder::new()
+        .construction(Construction::Derivatives)
+        .build(vec![((), TokenUses::Default, regex.clone())])
+}
+
+/// States from which an accepting state can be reached using bytes of `alphabet`.
+fn live_states(dfa: &TokensDFA<()>, alphabet: &CharClass) -> Vec<bool> {
+    let mut live: Vec<bool> = dfa.finals.iter().map(|(token, _)| token.is_some()).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for state in 0..dfa.states as usize {
+            if live[state] {
+                continue;
+            }
+            let reaches = alphabet.bytes().any(|byte| {
+                let next = dfa.next(state as State, byte);
+                next != ERROR_STATE && live[next as usize]
+            });
+            if reaches {
+                live[state] = true;
+                changed = true;
+            }
+        }
+    }
+    live
+}
+
+fn shortest_path(dfa: &TokensDFA<()>, from: State, alphabet: &CharClass) -> Option<Vec<u8>> {
+    let mut parents: Vec<Option<(State, u8)>> = vec![None; dfa.states as usize];
+    let mut seen = vec![false; dfa.states as usize];
+    let mut queue = VecDeque::from([from]);
+    seen[from as usize] = true;
+
+    while let Some(state) = queue.pop_front() {
+        if dfa.finals[state as usize].0.is_some() {
+            let mut path = Vec::new();
+            let mut current = state;
+            while let Some((parent, byte)) = parents[current as usize] {
+                path.push(byte);
+                current = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for byte in alphabet.bytes() {
+            let next = dfa.next(state, byte);
+            if next == ERROR_STATE || seen[next as usize] {
+                continue;
+            }
+            seen[next as usize] = true;
+            parents[next as usize] = Some((state, byte));
+            queue.push_back(next);
+        }
+    }
+    None
+}
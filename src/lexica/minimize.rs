@@ -0,0 +1,207 @@
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{State, TokensDFA, ERROR_STATE},
+    tokens::TokenUses
+};
+
+impl<T> TokensDFA<T> where T: Eq + Copy + Hash {
+    /// Merges equivalent states with Hopcroft's algorithm. States accepting different tokens,
+    /// the same token with different `TokenUses` or different lexeme rules are never merged,
+    /// and states that can't reach any accepting state fold into the error state.
+    pub fn minimize(self) -> Self {
+        let states = self.states as usize;
+        // The error state becomes an explicit sink, so every state has an edge for each class.
+        let sink = states;
+        let target = |state: usize, class: usize| {
+            if state == sink {
+                return sink;
+            }
+            match self.next_by_class(state as State, class) {
+                ERROR_STATE => sink,
+                next => next as usize,
+            }
+        };
+
+        let mut inverse: Vec<Vec<(usize, usize)>> = vec![Vec::new(); states + 1];
+        for state in 0..=states {
+            for class in 0..self.class_count {
+                inverse[target(state, class)].push((class, state));
+            }
+        }
+
+        let mut keys: Vec<(Option<T>, TokenUses, Option<usize>)> = vec![(None, TokenUses::Default, None)];
+        let mut groups = vec![0; states + 1];
+        for (group, ((token, mask), rule)) in groups.iter_mut().zip(self.finals.iter().zip(&self.rules)) {
+            let key = (*token, *mask, *rule);
+            *group = match keys.iter().position(|other| *other == key) {
+                Some(group) => group,
+                None => {
+                    keys.push(key);
+                    keys.len() - 1
+                }
+            };
+        }
+        let mut partition = Partition::new(&groups, keys.len());
+
+        // Every initial block but the largest one has to split the others.
+        let largest = (0..partition.len()).max_by_key(|block| partition.size(*block)).unwrap_or(0);
+        let mut pending: Vec<usize> = (0..partition.len()).filter(|block| *block != largest).collect();
+        let mut in_pending = vec![false; partition.len()];
+        for block in &pending {
+            in_pending[*block] = true;
+        }
+
+        while let Some(splitter) = pending.pop() {
+            in_pending[splitter] = false;
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); self.class_count];
+            for state in partition.members(splitter) {
+                for (class, from) in &inverse[*state] {
+                    predecessors[*class].push(*from);
+                }
+            }
+            for sources in predecessors.iter().filter(|sources| !sources.is_empty()) {
+                let mut touched = Vec::new();
+                for state in sources {
+                    if partition.mark(*state) {
+                        touched.push(partition.block_of[*state]);
+                    }
+                }
+                touched.sort();
+                touched.dedup();
+                for block in touched {
+                    let Some(new) = partition.split(block) else { continue };
+                    in_pending.push(false);
+                    let smaller = if in_pending[block] || partition.size(new) <= partition.size(block) {
+                        new
+                    } else {
+                        block
+                    };
+                    if !in_pending[smaller] {
+                        in_pending[smaller] = true;
+                        pending.push(smaller);
+                    }
+                }
+            }
+        }
+
+        // Blocks numbered by their first state, so the initial state keeps number 0.
+        let sink_block = partition.block_of[sink];
+        let mut numbers = vec![ERROR_STATE; partition.len()];
+        let mut representatives = Vec::new();
+        for state in 0..states {
+            let block = partition.block_of[state];
+            if block != sink_block && numbers[block] == ERROR_STATE {
+                numbers[block] = representatives.len() as State;
+                representatives.push(state);
+            }
+        }
+        if representatives.is_empty() {
+            // No token can be matched at all, only the initial state is left.
+            return Self {
+                states: 1,
+                finals: vec![(None, TokenUses::Default)],
+                classes: self.classes,
+                class_count: self.class_count,
+                transitions: vec![ERROR_STATE; self.class_count],
+                rules: vec![None],
+                lexeme_rules: self.lexeme_rules,
+            };
+        }
+
+        let transitions = representatives.iter()
+            .flat_map(|state| (0..self.class_count).map(|class| numbers[partition.block_of[target(*state, class)]]))
+            .collect();
+        let finals = representatives.iter().map(|state| self.finals[*state]).collect();
+        let rules = representatives.iter().map(|state| self.rules[*state]).collect();
+
+        Self {
+            states: representatives.len() as u32,
+            finals,
+            classes: self.classes,
+            class_count: self.class_count,
+            transitions,
+            rules,
+            lexeme_rules: self.lexeme_rules,
+        }
+    }
+}
+
+/// Blocks of states kept as contiguous slices of `elements`, so marking a state and
+/// splitting the marked ones off a block cost as much as the marked states.
+struct Partition {
+    elements: Vec<usize>,
+    location: Vec<usize>,
+    block_of: Vec<usize>,
+    /// Start and end of each block in `elements`, its marked states come first.
+    blocks: Vec<(usize, usize)>,
+    marked: Vec<usize>,
+}
+
+impl Partition {
+    fn new(groups: &[usize], count: usize) -> Self {
+        let mut elements: Vec<usize> = (0..groups.len()).collect();
+        elements.sort_by_key(|state| groups[*state]);
+        let mut location = vec![0; groups.len()];
+        let mut blocks: Vec<(usize, usize)> = Vec::new();
+        let mut numbers = vec![usize::MAX; count];
+        let mut block_of = vec![0; groups.len()];
+        for (index, state) in elements.iter().enumerate() {
+            location[*state] = index;
+            let group = groups[*state];
+            if numbers[group] == usize::MAX {
+                numbers[group] = blocks.len();
+                blocks.push((index, index));
+            }
+            block_of[*state] = numbers[group];
+            blocks[numbers[group]].1 = index + 1;
+        }
+        let marked = vec![0; blocks.len()];
+        Self { elements, location, block_of, blocks, marked }
+    }
+
+    fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    fn size(&self, block: usize) -> usize {
+        self.blocks[block].1 - self.blocks[block].0
+    }
+
+    fn members(&self, block: usize) -> &[usize] {
+        &self.elements[self.blocks[block].0..self.blocks[block].1]
+    }
+
+    /// Moves `state` to the marked front of its block, false if it already was there.
+    fn mark(&mut self, state: usize) -> bool {
+        let block = self.block_of[state];
+        let index = self.location[state];
+        let first_unmarked = self.blocks[block].0 + self.marked[block];
+        if index < first_unmarked {
+            return false;
+        }
+        let other = self.elements[first_unmarked];
+        self.elements.swap(index, first_unmarked);
+        self.location[other] = index;
+        self.location[state] = first_unmarked;
+        self.marked[block] += 1;
+        true
+    }
+
+    /// Turns the marked states of `block` into a new block, unless all or none are marked.
+    fn split(&mut self, block: usize) -> Option<usize> {
+        let marked = std::mem::take(&mut self.marked[block]);
+        if marked == 0 || marked == self.size(block) {
+            return None;
+        }
+        let (start, end) = self.blocks[block];
+        let new = self.blocks.len();
+        self.blocks.push((start, start + marked));
+        self.marked.push(0);
+        self.blocks[block] = (start + marked, end);
+        for state in &self.elements[start..start + marked] {
+            self.block_of[*state] = new;
+        }
+        Some(new)
+    }
+}
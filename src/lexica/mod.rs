@@ -1,4 +1,24 @@
 pub mod tokens;
 pub mod regex;
+pub mod class;
+pub mod unicode;
+pub mod case;
+pub mod simplify;
+pub mod display;
+pub mod derivative;
+pub mod equivalence;
+pub mod definitions;
+pub mod capture;
+pub mod generate;
+mod unicode_tables;
+pub mod parse;
 pub mod automata;
+pub mod minimize;
+pub mod compressed;
+pub mod serialize;
+pub mod codegen;
+pub mod precompile;
+pub mod diagnostics;
+pub mod pike;
+pub mod dot;
 pub mod analysis;
@@ -0,0 +1,517 @@
+use std::fmt;
+
+use crate::lexica::{
+    class::CharClass,
+    regex::{Regex, MAX_REPETITION},
+    unicode::{negate_code_points, UnicodeCategory}
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexParseErrorKind {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    UnclosedGroup,
+    UnclosedClass,
+    UnopenedGroup,
+    NothingToRepeat,
+    InvalidEscape(char),
+    InvalidRange(char, char),
+    InvalidRepetition,
+    /// Bounds unrolling the repeated regex to more than `MAX_REPETITION` copies.
+    RepetitionTooLarge,
+    UnknownProperty(String),
+    /// A class with both `\xHH` bytes above `\x7F` and non ascii code points, which can't
+    /// be told apart once encoded.
+    MixedBytesAndCodePoints,
+    UndefinedName(String),
+    InvalidGroupName,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexParseError {
+    pub offset: usize,
+    pub kind: RegexParseErrorKind,
+}
+
+impl fmt::Display for RegexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error at byte {}: ", self.offset)?;
+        match &self.kind {
+            RegexParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of pattern"),
+            RegexParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            RegexParseErrorKind::UnclosedGroup => write!(f, "group opened and never closed"),
+            RegexParseErrorKind::UnclosedClass => write!(f, "character class opened and never closed"),
+            RegexParseErrorKind::UnopenedGroup => write!(f, "')' without a matching '('"),
+            RegexParseErrorKind::NothingToRepeat => write!(f, "repetition operator without an expression"),
+            RegexParseErrorKind::InvalidEscape(c) => write!(f, "invalid escape '\\{}'", c),
+            RegexParseErrorKind::InvalidRange(a, b) => write!(f, "invalid range '{}-{}'", a, b),
+            RegexParseErrorKind::InvalidRepetition => write!(f, "invalid repetition bounds"),
+            RegexParseErrorKind::RepetitionTooLarge => write!(f, "repetition above {} copies", MAX_REPETITION),
+            RegexParseErrorKind::UnknownProperty(name) => write!(f, "unknown unicode property '{}'", name),
+            RegexParseErrorKind::MixedBytesAndCodePoints => write!(f, "class mixes bytes above '\\x7F' with non ascii characters, use '\\u{{..}}' for code points"),
+            RegexParseErrorKind::UndefinedName(name) => write!(f, "undefined name '{{{}}}'", name),
+            RegexParseErrorKind::InvalidGroupName => write!(f, "expected a group name as in '(?<name>...)'"),
+        }
+    }
+}
+
+impl std::error::Error for RegexParseError {}
+
+impl Regex {
+    /// Parses a textual regex such as `[a-zA-Z_][a-zA-Z0-9_]*` into a `Regex`.
+    ///
+    /// Supports literals, escapes (`\n`, `\t`, `\r`, `\0`, `\xHH`, `\u{HHHH}`, `\d`, `\w`,
+    /// `\s` and escaped metacharacters), `.`, character classes with ranges and `^` negation,
+    /// groups, `|`, `&` (intersection), prefix `~` (complement), `*`, `+`, `?` and `{m}`,
+    /// `{m,}`, `{m,n}`. `\xHH` is always a single byte and `\u{..}` a UTF-8 encoded code
+    /// point. Classes containing non ascii characters, `\u{..}` or a `\p{..}` property
+    /// (`L`, `Nd`, `XID_Start`, `XID_Continue`) match UTF-8 encoded code points instead
+    /// of single bytes, so they can't also hold bytes above `\x7F`. A top level `head/tail`
+    /// is a trailing context where only `head` is consumed, a literal slash must be escaped
+    /// as `\/`. `(?<name>...)` is a named capture group. References to named fragments
+    /// (`{DIGIT}`) are only available through `RegexDefinitions::parse`.
+    pub fn parse(pattern: &str) -> Result<Regex, RegexParseError> {
+        parse_with_names(pattern, &mut |_| None)
+    }
+}
+
+/// Parses `pattern`, replacing every `{NAME}` by the regex returned by `resolve`.
+pub(crate) fn parse_with_names(pattern: &str, resolve: &mut dyn FnMut(&str) -> Option<Regex>) -> Result<Regex, RegexParseError> {
+    let mut parser = Parser { pattern, pos: 0, resolve };
+    let mut regex = parser.parse_union()?;
+    if parser.eat('/') {
+        regex = Regex::followed_by(regex, parser.parse_union()?);
+    }
+    match parser.peek() {
+        None => Ok(regex),
+        Some(')') => Err(parser.error(RegexParseErrorKind::UnopenedGroup)),
+        Some(c) => Err(parser.error(RegexParseErrorKind::UnexpectedChar(c))),
+    }
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    pos: usize,
+    resolve: &'a mut dyn FnMut(&str) -> Option<Regex>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn error(&self, kind: RegexParseErrorKind) -> RegexParseError {
+        RegexParseError { offset: self.pos, kind }
+    }
+
+    fn error_at(&self, offset: usize, kind: RegexParseErrorKind) -> RegexParseError {
+        RegexParseError { offset, kind }
+    }
+
+    fn parse_union(&mut self) -> Result<Regex, RegexParseError> {
+        let mut branches = vec![self.parse_intersection()?];
+        while self.eat('|') {
+            branches.push(self.parse_intersection()?);
+        }
+        if branches.len() == 1 {
+            return Ok(branches.pop().unwrap());
+        }
+        Ok(Regex::Union(branches))
+    }
+
+    /// Operands of `&` can't be left out, an unescaped `&&` would otherwise match the empty string.
+    fn parse_intersection(&mut self) -> Result<Regex, RegexParseError> {
+        let start = self.pos;
+        let first = self.parse_concat()?;
+        if self.peek() != Some('&') {
+            return Ok(first);
+        }
+        if self.pos == start {
+            return Err(self.error(RegexParseErrorKind::UnexpectedChar('&')));
+        }
+        let mut branches = vec![first];
+        while self.eat('&') {
+            let start = self.pos;
+            let branch = self.parse_concat()?;
+            if self.pos == start {
+                return Err(match self.peek() {
+                    None => self.error(RegexParseErrorKind::UnexpectedEnd),
+                    Some(c) => self.error(RegexParseErrorKind::UnexpectedChar(c)),
+                });
+            }
+            branches.push(branch);
+        }
+        Ok(Regex::Intersection(branches))
+    }
+
+    fn parse_concat(&mut self) -> Result<Regex, RegexParseError> {
+        let mut items: Vec<Regex> = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == '&' || c == ')' || c == '/' {
+                break;
+            }
+            items.push(self.parse_unit()?);
+        }
+        Ok(concat_items(items))
+    }
+
+    fn parse_unit(&mut self) -> Result<Regex, RegexParseError> {
+        if self.eat('~') {
+            return Ok(!self.parse_unit()?);
+        }
+        let atom = self.parse_atom()?;
+        self.parse_repetitions(atom)
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex, RegexParseError> {
+        let start = self.pos;
+        match self.bump() {
+            None => Err(self.error(RegexParseErrorKind::UnexpectedEnd)),
+            Some('(') => {
+                let name = if self.eat('?') { Some(self.parse_group_name()?) } else { None };
+                let mut inner = self.parse_union()?;
+                if self.peek() == Some('/') {
+                    return Err(self.error(RegexParseErrorKind::UnexpectedChar('/')));
+                }
+                if !self.eat(')') {
+                    return Err(self.error_at(start, RegexParseErrorKind::UnclosedGroup));
+                }
+                if let Some(name) = name {
+                    inner = Regex::capture(&name, inner);
+                }
+                Ok(inner)
+            },
+            Some('{') if self.starts_name(self.pos) => self.parse_name(start),
+            Some('[') => self.parse_class(start),
+            Some('.') => Ok(Regex::not_in(b"\n")),
+            Some('\\') => match self.parse_escape()? {
+                Escape::Byte(byte) => Ok(Regex::Char(byte)),
+                Escape::Char(c) => Ok(Regex::from_chars(c..=c)),
+                Escape::Set(class) => Ok(Regex::from_class(class)),
+                Escape::Property(category) => Ok(Regex::unicode(category)),
+            },
+            Some('*' | '+' | '?' | '{') => Err(self.error_at(start, RegexParseErrorKind::NothingToRepeat)),
+            Some(c) if c.is_ascii() => Ok(Regex::Char(c as u8)),
+            Some(c) => {
+                let mut buf = [0; 4];
+                Ok(Regex::from_word(c.encode_utf8(&mut buf)))
+            }
+        }
+    }
+
+    fn parse_repetitions(&mut self, mut atom: Regex) -> Result<Regex, RegexParseError> {
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.bump();
+                    atom = Regex::repeat(atom);
+                },
+                Some('+') => {
+                    self.bump();
+                    atom = Regex::plus(atom);
+                },
+                Some('?') => {
+                    self.bump();
+                    atom = Regex::optional(atom);
+                },
+                Some('{') if !self.starts_name(self.pos + 1) => {
+                    let start = self.pos;
+                    let (min, max) = self.parse_bounds()?;
+                    if !atom.fits_repetition(max.unwrap_or(min)) {
+                        return Err(self.error_at(start, RegexParseErrorKind::RepetitionTooLarge));
+                    }
+                    atom = Regex::Bounded(Box::new(atom), min, max);
+                },
+                _ => return Ok(atom),
+            }
+        }
+    }
+
+    /// Whether a name starts at `offset`, telling a `{NAME}` reference apart from `{m,n}` bounds.
+    fn starts_name(&self, offset: usize) -> bool {
+        self.pattern[offset..].chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    }
+
+    fn parse_name(&mut self, start: usize) -> Result<Regex, RegexParseError> {
+        let name_start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            self.bump();
+        }
+        let name = &self.pattern[name_start..self.pos];
+        match self.peek() {
+            Some('}') => self.bump(),
+            Some(c) => return Err(self.error(RegexParseErrorKind::UnexpectedChar(c))),
+            None => return Err(self.error(RegexParseErrorKind::UnexpectedEnd)),
+        };
+        match (self.resolve)(name) {
+            Some(regex) => Ok(regex),
+            None => Err(self.error_at(start, RegexParseErrorKind::UndefinedName(name.to_string()))),
+        }
+    }
+
+    /// Parses the `<name>` (or `P<name>`) following `(?`.
+    fn parse_group_name(&mut self) -> Result<String, RegexParseError> {
+        let start = self.pos;
+        self.eat('P');
+        if !self.eat('<') || !self.starts_name(self.pos) {
+            return Err(self.error_at(start, RegexParseErrorKind::InvalidGroupName));
+        }
+        let name_start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            self.bump();
+        }
+        let name = self.pattern[name_start..self.pos].to_string();
+        if !self.eat('>') {
+            return Err(self.error_at(start, RegexParseErrorKind::InvalidGroupName));
+        }
+        Ok(name)
+    }
+
+    fn parse_bounds(&mut self) -> Result<(u32, Option<u32>), RegexParseError> {
+        let start = self.pos;
+        self.bump();
+        let min = self.parse_number().ok_or_else(|| self.error_at(start, RegexParseErrorKind::InvalidRepetition))?;
+        let max = if self.eat(',') {
+            if self.peek() == Some('}') {
+                None
+            } else {
+                let max = self.parse_number().ok_or_else(|| self.error_at(start, RegexParseErrorKind::InvalidRepetition))?;
+                Some(max)
+            }
+        } else {
+            Some(min)
+        };
+        if !self.eat('}') {
+            return Err(self.error_at(start, RegexParseErrorKind::InvalidRepetition));
+        }
+        if let Some(max) = max {
+            if max < min {
+                return Err(self.error_at(start, RegexParseErrorKind::InvalidRepetition));
+            }
+        }
+        Ok((min, max))
+    }
+
+    fn parse_number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.bump();
+        }
+        self.pattern[start..self.pos].parse().ok()
+    }
+
+    fn parse_escape(&mut self) -> Result<Escape, RegexParseError> {
+        let start = self.pos - 1;
+        let c = self.bump().ok_or_else(|| self.error(RegexParseErrorKind::UnexpectedEnd))?;
+        let escape = match c {
+            'n' => Escape::Byte(b'\n'),
+            'r' => Escape::Byte(b'\r'),
+            't' => Escape::Byte(b'\t'),
+            '0' => Escape::Byte(b'\0'),
+            'x' => {
+                // `from_str_radix` alone would take a sign, as in `\x+f`.
+                let digits = self.pattern.get(self.pos..self.pos + 2)
+                    .filter(|hex| hex.bytes().all(|digit| digit.is_ascii_hexdigit()))
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match digits {
+                    Some(byte) => {
+                        self.pos += 2;
+                        Escape::Byte(byte)
+                    },
+                    None => return Err(self.error_at(start, RegexParseErrorKind::InvalidEscape('x'))),
+                }
+            },
+            'u' => {
+                let code_point = self.pattern[self.pos..].strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .filter(|(hex, _)| !hex.is_empty() && hex.len() <= 6 && hex.bytes().all(|digit| digit.is_ascii_hexdigit()))
+                    .and_then(|(hex, _)| Some((hex.len(), char::from_u32(u32::from_str_radix(hex, 16).ok()?)?)));
+                match code_point {
+                    Some((length, c)) => {
+                        self.pos += length + 2;
+                        Escape::Char(c)
+                    },
+                    None => return Err(self.error_at(start, RegexParseErrorKind::InvalidEscape('u'))),
+                }
+            },
+            'd' => Escape::Set(CharClass::from_range(b'0'..=b'9')),
+            'w' => Escape::Set(CharClass::from_ranges([b'a'..=b'z', b'A'..=b'Z', b'0'..=b'9', b'_'..=b'_'])),
+            's' => Escape::Set(CharClass::from_bytes(b" \t\n\r\x0b\x0c")),
+            'p' => return self.parse_property(start),
+            c if c.is_ascii_punctuation() => Escape::Byte(c as u8),
+            c => return Err(self.error_at(start, RegexParseErrorKind::InvalidEscape(c))),
+        };
+        Ok(escape)
+    }
+
+    fn parse_class(&mut self, start: usize) -> Result<Regex, RegexParseError> {
+        let negated = self.eat('^');
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        let mut unicode = false;
+        // Offset of the first `\xHH` above `\x7F`, those can't be code points.
+        let mut high_byte = None;
+        let mut first = true;
+
+        loop {
+            let item_start = self.pos;
+            let low = match self.bump() {
+                None => return Err(self.error_at(start, RegexParseErrorKind::UnclosedClass)),
+                Some(']') if !first => break,
+                Some('\\') => self.parse_escape()?,
+                Some(c) if c.is_ascii() => Escape::Byte(c as u8),
+                Some(c) => Escape::Char(c),
+            };
+            first = false;
+
+            let low = match low {
+                Escape::Set(set) => {
+                    ranges.extend(set.ranges().map(|range| (*range.start() as u32, *range.end() as u32)));
+                    continue;
+                },
+                Escape::Property(category) => {
+                    unicode = true;
+                    ranges.extend_from_slice(category.ranges());
+                    continue;
+                },
+                Escape::Byte(byte) => {
+                    if !byte.is_ascii() {
+                        high_byte.get_or_insert(item_start);
+                    }
+                    byte as u32
+                },
+                Escape::Char(c) => {
+                    unicode |= !c.is_ascii();
+                    c as u32
+                },
+            };
+
+            let is_range = self.peek() == Some('-') && self.pattern[self.pos + 1..].chars().next().is_some_and(|c| c != ']');
+            if !is_range {
+                ranges.push((low, low));
+                continue;
+            }
+            self.bump();
+            let high = match self.bump() {
+                None => return Err(self.error_at(start, RegexParseErrorKind::UnclosedClass)),
+                Some('\\') => match self.parse_escape()? {
+                    Escape::Byte(byte) => {
+                        if !byte.is_ascii() {
+                            high_byte.get_or_insert(item_start);
+                        }
+                        byte as u32
+                    },
+                    Escape::Char(c) => {
+                        unicode |= !c.is_ascii();
+                        c as u32
+                    },
+                    Escape::Set(_) | Escape::Property(_) => {
+                        return Err(self.error_at(item_start, RegexParseErrorKind::InvalidRange(to_char(low), '\\')));
+                    },
+                },
+                Some(c) => {
+                    unicode |= !c.is_ascii();
+                    c as u32
+                },
+            };
+            if high < low {
+                return Err(self.error_at(item_start, RegexParseErrorKind::InvalidRange(to_char(low), to_char(high))));
+            }
+            ranges.push((low, high));
+        }
+
+        if unicode {
+            if let Some(offset) = high_byte {
+                return Err(self.error_at(offset, RegexParseErrorKind::MixedBytesAndCodePoints));
+            }
+            if negated {
+                ranges = negate_code_points(&ranges);
+            }
+            return Ok(Regex::from_code_points(&ranges));
+        }
+        let mut class = CharClass::from_ranges(ranges.into_iter().map(|(low, high)| low as u8..=high as u8));
+        if negated {
+            class = class.negate();
+        }
+        Ok(Regex::from_class(class))
+    }
+
+    fn parse_property(&mut self, start: usize) -> Result<Escape, RegexParseError> {
+        if !self.eat('{') {
+            return Err(self.error_at(start, RegexParseErrorKind::InvalidEscape('p')));
+        }
+        let name_start = self.pos;
+        while let Some(c) = self.bump() {
+            if c == '}' {
+                let name = &self.pattern[name_start..self.pos - 1];
+                return match UnicodeCategory::from_name(name) {
+                    Some(category) => Ok(Escape::Property(category)),
+                    None => Err(self.error_at(start, RegexParseErrorKind::UnknownProperty(name.to_string()))),
+                };
+            }
+        }
+        Err(self.error(RegexParseErrorKind::UnexpectedEnd))
+    }
+}
+
+enum Escape {
+    Byte(u8),
+    Char(char),
+    Set(CharClass),
+    Property(UnicodeCategory),
+}
+
+fn to_char(code_point: u32) -> char {
+    char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+fn concat_items(items: Vec<Regex>) -> Regex {
+    let mut merged: Vec<Regex> = Vec::new();
+    for item in items {
+        let bytes = match item {
+            Regex::Empty => continue,
+            Regex::Char(c) => vec![c],
+            Regex::Word(word) => word,
+            item => {
+                merged.push(item);
+                continue;
+            }
+        };
+        match merged.last_mut() {
+            Some(Regex::Word(word)) => word.extend(bytes),
+            Some(last @ Regex::Char(_)) => {
+                if let Regex::Char(prev) = *last {
+                    let mut word = vec![prev];
+                    word.extend(bytes);
+                    *last = Regex::Word(word);
+                }
+            },
+            _ if bytes.len() == 1 => merged.push(Regex::Char(bytes[0])),
+            _ => merged.push(Regex::Word(bytes)),
+        }
+    }
+    match merged.len() {
+        0 => Regex::Empty,
+        1 => merged.pop().unwrap(),
+        _ => Regex::Concat(merged),
+    }
+}
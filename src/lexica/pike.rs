@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lexica::{
+    automata::{LexemeRules, State, TokensNFA, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// Runs the `TokensNFA` of a token set directly, keeping the set of live states (threads)
+/// instead of building the DFA, so the cost of a large token set is paid per byte read
+/// rather than by a subset construction that may blow up.
+pub struct PikeVM<T> where T: Eq + Copy + Hash {
+    nfa: TokensNFA<T>,
+    /// Final NFA state to the index of the first token declared with it.
+    accepting: HashMap<State, usize>,
+    /// States already added to the thread list being built, reused between steps.
+    visited: RefCell<Vec<bool>>,
+    /// Index in `lexeme_rules.regexs` of the rules of each declaration.
+    rules: Vec<Option<usize>>,
+    pub lexeme_rules: LexemeRules<T>,
+}
+
+impl<T> PikeVM<T> where T: Eq + Copy + Hash {
+    pub fn new(tokens_regexs: Vec<(T, TokenUses, Regex)>) -> Self {
+        let (lexeme_rules, rules) = LexemeRules::with_declarations(&tokens_regexs);
+        let nfa = TokensNFA::new(tokens_regexs);
+        let mut accepting = HashMap::new();
+        for (index, (_, _, state)) in nfa.finals.iter().enumerate() {
+            accepting.entry(*state).or_insert(index);
+        }
+        let visited = RefCell::new(vec![false; nfa.states as usize]);
+        Self { nfa, accepting, visited, rules, lexeme_rules }
+    }
+
+    /// Threads before reading anything.
+    pub fn start(&self) -> Vec<State> {
+        self.closure(vec![INIT_STATE])
+    }
+
+    /// Advances every thread over `byte`, an empty result means no token can match anymore.
+    pub fn step(&self, threads: &[State], byte: u8) -> Vec<State> {
+        self.closure(self.nfa.step(threads, byte))
+    }
+
+    /// The token accepted by the threads and the lexeme rules of its declaration, the
+    /// first declared one wins.
+    pub fn accepted(&self, threads: &[State]) -> Option<(T, TokenUses, Option<usize>)> {
+        let index = threads.iter().filter_map(|state| self.accepting.get(state)).min()?;
+        let (name, mask, _) = self.nfa.finals[*index];
+        Some((name, mask, self.rules[*index]))
+    }
+
+    pub fn test_string(&self, string: String) -> Option<T> {
+        let mut threads = self.start();
+        for char in string.as_bytes() {
+            threads = self.step(&threads, *char);
+            if threads.is_empty() {
+                return None;
+            }
+        }
+        self.accepted(&threads).map(|(name, _, _)| name)
+    }
+
+    fn closure(&self, mut threads: Vec<State>) -> Vec<State> {
+        let mut visited = self.visited.borrow_mut();
+        threads.retain(|state| !std::mem::replace(&mut visited[*state as usize], true));
+        let mut index = 0;
+        while index < threads.len() {
+            if let Some(nexts) = self.nfa.empty_transitions.get(&threads[index]) {
+                for next in nexts {
+                    if !visited[*next as usize] {
+                        visited[*next as usize] = true;
+                        threads.push(*next);
+                    }
+                }
+            }
+            index += 1;
+        }
+        for state in &threads {
+            visited[*state as usize] = false;
+        }
+        threads
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::lexica::{
+    automata::TokensDFA,
+    diagnostics::{spec_warnings, LexerSpecWarning},
+    parse::RegexParseError,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+/// A token of a spec given as text, for building the lexer ahead of time, e.g. by `lexer!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSpec {
+    pub name: String,
+    pub uses: TokenUses,
+    pub pattern: String,
+    /// Tokens this one is meant to win against when both accept the same input, like a
+    /// keyword over identifiers.
+    pub over: Vec<String>,
+}
+
+impl TokenSpec {
+    pub fn new(name: &str, uses: TokenUses, pattern: &str) -> Self {
+        Self { name: name.to_string(), uses, pattern: pattern.to_string(), over: Vec::new() }
+    }
+
+    pub fn over(mut self, names: &[&str]) -> Self {
+        self.over.extend(names.iter().map(|name| name.to_string()));
+        self
+    }
+}
+
+/// Mistakes in a spec, `token` is the index of the declaration at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    Pattern { token: usize, error: RegexParseError },
+    /// `over` names a token that the spec doesn't declare.
+    UnknownOver { token: usize, name: String },
+    /// The token wins inputs also accepted by `losers`, without saying it is meant to.
+    Conflict { token: usize, losers: Vec<String>, example: Vec<u8> },
+    /// The token is never produced, earlier declarations win everything it accepts.
+    Shadowed { token: usize, by: Vec<String>, example: Vec<u8> },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecError::Pattern { error, .. } => write!(f, "invalid regex: {}", error),
+            SpecError::UnknownOver { name, .. } => write!(f, "`over {}` names no token of the spec", name),
+            SpecError::Conflict { losers, example, .. } => write!(
+                f, "also accepted by {} (e.g. {:?}), this token wins as it is declared first; write `over {}` if that is intended",
+                losers.join(", "), String::from_utf8_lossy(example), losers.join(", ")
+            ),
+            SpecError::Shadowed { by, example, .. } => write!(
+                f, "never produced, everything it accepts (e.g. {:?}) is taken by {} declared before it",
+                String::from_utf8_lossy(example), by.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+/// A checked spec: the distinct token names in declaration order, and the minimized DFA
+/// saved with `TokensDFA::to_bytes`, its tokens being indexes into `names`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precompiled {
+    pub names: Vec<String>,
+    pub dfa: Vec<u8>,
+}
+
+/// Parses and checks the spec, then builds its DFA. Conflicts are errors unless the winner
+/// lists the losers in `over`.
+pub fn precompile(specs: &[TokenSpec]) -> Result<Precompiled, Vec<SpecError>> {
+    let mut errors = Vec::new();
+    let mut declarations = Vec::new();
+    for (token, spec) in specs.iter().enumerate() {
+        match Regex::parse(&spec.pattern) {
+            Ok(regex) => declarations.push((token, spec.uses, regex)),
+            Err(error) => errors.push(SpecError::Pattern { token, error }),
+        }
+        for name in &spec.over {
+            if !specs.iter().any(|other| other.name == *name) {
+                errors.push(SpecError::UnknownOver { token, name: name.clone() });
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    for warning in spec_warnings(&declarations) {
+        match warning {
+            LexerSpecWarning::Conflict { tokens, winner, example, .. } => {
+                let spec = &specs[winner];
+                let losers: Vec<String> = tokens.iter()
+                    .map(|token| specs[*token].name.clone())
+                    .filter(|name| *name != spec.name && !spec.over.contains(name))
+                    .collect();
+                let reported = errors.iter().any(|error| matches!(error, SpecError::Conflict { token, losers: other, .. } if *token == winner && *other == losers));
+                if !losers.is_empty() && !reported {
+                    errors.push(SpecError::Conflict { token: winner, losers, example });
+                }
+            },
+            LexerSpecWarning::Shadowed { token, by, example } => {
+                let by: Vec<String> = by.iter().map(|other| specs[*other].name.clone()).collect();
+                if by.iter().any(|name| *name != specs[token].name) {
+                    errors.push(SpecError::Shadowed { token, by, example });
+                }
+            },
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for spec in specs {
+        if !names.contains(&spec.name) {
+            names.push(spec.name.clone());
+        }
+    }
+    let ids: Vec<u32> = (0..names.len() as u32).collect();
+    let tokens = declarations.into_iter()
+        .map(|(token, uses, regex)| (names.iter().position(|name| *name == specs[token].name).unwrap() as u32, uses, regex))
+        .collect();
+    let dfa = TokensDFA::new(tokens).minimize().to_bytes(&ids).unwrap();
+    Ok(Precompiled { names, dfa })
+}
@@ -1,11 +1,26 @@
-use std::ops::{Add, BitOr, RangeInclusive};
+use std::ops::{Add, BitAnd, BitOr, Not, RangeInclusive};
 
+use crate::lexica::class::CharClass;
+
+/// Largest number of copies a bounded repetition may unroll its regex to, counting the
+/// repetitions nested inside it, since every copy turns into automaton states.
+pub const MAX_REPETITION: u32 = 1000;
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Regex {
     Concat(Vec<Regex>),
     Union(Vec<Regex>),
     Repeat(Box<Regex>),
+    Bounded(Box<Regex>, u32, Option<u32>),
+    Intersection(Vec<Regex>),
+    Complement(Box<Regex>),
+    /// Lex style trailing context `head/tail`: matches `head` only when followed by `tail`.
+    Trailing(Box<Regex>, Box<Regex>),
+    /// Named group whose span inside the lexeme is reported on the token.
+    Capture(String, Box<Regex>),
     Word(Vec<u8>),
     Char(u8),
+    Class(CharClass),
     Empty
 }
 
@@ -29,10 +44,26 @@ impl Clone for Regex {
             Regex::Repeat(re) => {
                 Regex::Repeat(re.clone())
             },
+            Regex::Bounded(re, min, max) => {
+                Regex::Bounded(re.clone(), *min, *max)
+            },
+            Regex::Intersection(regexs) => {
+                Regex::Intersection(regexs.to_vec())
+            },
+            Regex::Complement(re) => {
+                Regex::Complement(re.clone())
+            },
+            Regex::Trailing(head, tail) => {
+                Regex::Trailing(head.clone(), tail.clone())
+            },
+            Regex::Capture(name, re) => {
+                Regex::Capture(name.clone(), re.clone())
+            },
             Regex::Word(s) => {
                 Regex::Word(s.clone())
             },
             Regex::Char(c) => Regex::Char(*c),
+            Regex::Class(class) => Regex::Class(class.clone()),
             Regex::Empty => Regex::Empty,
         }
     }
@@ -64,6 +95,26 @@ impl BitOr for Regex {
     }
 }
 
+impl BitAnd for Regex {
+    type Output = Regex;
+
+    fn bitand(self, other: Self) -> Regex {
+        if let Regex::Intersection(mut vec) = self {
+            vec.push(other);
+            return Regex::Intersection(vec);
+        }
+        Regex::Intersection(vec![self, other])
+    }
+}
+
+impl Not for Regex {
+    type Output = Regex;
+
+    fn not(self) -> Regex {
+        Regex::Complement(Box::new(self))
+    }
+}
+
 impl Regex {
     pub fn new<A>(args: A) -> Regex
         where A: IntoRegex {
@@ -71,11 +122,10 @@ impl Regex {
     }
 
     pub fn from_set(set: RangeInclusive<u8>) -> Regex {
-        let mut bytes = Vec::new();
-            for byte in set {
-                bytes.push(Regex::Char(byte));
-            }
-        Regex::Union(bytes)
+        Regex::Class(CharClass::from_range(set))
+    }
+    pub fn from_class(class: CharClass) -> Regex {
+        Regex::Class(class)
     }
     pub fn from_word(word: &str) -> Regex { 
         let vec_char: Vec<u8> = word.as_bytes().to_vec();
@@ -89,12 +139,99 @@ impl Regex {
     }
     
     pub fn any() -> Regex {
-        Regex::from_set(0..=255)
+        Regex::Class(CharClass::full())
+    }
+
+    pub fn one_of(bytes: &[u8]) -> Regex {
+        Regex::Class(CharClass::from_bytes(bytes))
+    }
+
+    pub fn not_in(bytes: &[u8]) -> Regex {
+        Regex::Class(CharClass::from_bytes(bytes).negate())
     }
     
     pub fn repeat(re: Regex) -> Regex {
         Regex::Repeat(Box::new(re.clone()))
     }
+
+    pub fn plus(re: Regex) -> Regex {
+        Regex::Bounded(Box::new(re), 1, None)
+    }
+
+    pub fn optional(re: Regex) -> Regex {
+        Regex::Bounded(Box::new(re), 0, Some(1))
+    }
+
+    /// Panics if the range is empty, like `3..=1`, or unrolls to more than `MAX_REPETITION` copies.
+    pub fn times(re: Regex, range: RangeInclusive<u32>) -> Regex {
+        assert!(range.start() <= range.end(), "empty repetition range {}..={}", range.start(), range.end());
+        assert!(re.fits_repetition(*range.end()), "repetition {}..={} above {} copies", range.start(), range.end(), MAX_REPETITION);
+        Regex::Bounded(Box::new(re), *range.start(), Some(*range.end()))
+    }
+
+    /// Panics if `min` unrolls to more than `MAX_REPETITION` copies.
+    pub fn at_least(re: Regex, min: u32) -> Regex {
+        assert!(re.fits_repetition(min), "repetition {}.. above {} copies", min, MAX_REPETITION);
+        Regex::Bounded(Box::new(re), min, None)
+    }
+
+    pub fn capture(name: &str, re: Regex) -> Regex {
+        Regex::Capture(name.to_string(), Box::new(re))
+    }
+
+    /// `head` followed by `context`, where only `head` is part of the lexeme.
+    pub fn followed_by(head: Regex, context: Regex) -> Regex {
+        Regex::Trailing(Box::new(head), Box::new(context))
+    }
+
+    /// Whether a `Trailing` appears anywhere in the regex.
+    pub fn has_trailing_context(&self) -> bool {
+        match self {
+            Regex::Trailing(..) => true,
+            Regex::Concat(regexs) | Regex::Union(regexs) | Regex::Intersection(regexs) => regexs.iter().any(Regex::has_trailing_context),
+            Regex::Repeat(re) | Regex::Bounded(re, _, _) | Regex::Complement(re) | Regex::Capture(_, re) => re.has_trailing_context(),
+            Regex::Word(_) | Regex::Char(_) | Regex::Class(_) | Regex::Empty => false,
+        }
+    }
+
+    /// A regex that matches no string at all, not even the empty one.
+    pub fn nothing() -> Regex {
+        Regex::Class(CharClass::new())
+    }
+
+    /// Whether repeating this regex `count` times stays within `MAX_REPETITION` copies.
+    pub(crate) fn fits_repetition(&self, count: u32) -> bool {
+        count as u64 * self.copies() <= MAX_REPETITION as u64
+    }
+
+    /// Copies of its innermost part this regex unrolls to through nested bounded repetitions.
+    fn copies(&self) -> u64 {
+        match self {
+            Regex::Concat(regexs) | Regex::Union(regexs) | Regex::Intersection(regexs) => {
+                regexs.iter().map(Regex::copies).max().unwrap_or(1)
+            },
+            Regex::Bounded(re, min, max) => (max.unwrap_or(*min).max(1) as u64).saturating_mul(re.copies()),
+            Regex::Repeat(re) | Regex::Complement(re) | Regex::Capture(_, re) => re.copies(),
+            Regex::Trailing(head, tail) => head.copies().max(tail.copies()),
+            Regex::Word(_) | Regex::Char(_) | Regex::Class(_) | Regex::Empty => 1,
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Regex::Concat(regexs) => regexs.iter().all(Regex::is_nullable),
+            Regex::Union(regexs) => regexs.iter().any(Regex::is_nullable),
+            Regex::Repeat(_) => true,
+            Regex::Bounded(re, min, max) => max.is_none_or(|max| max >= *min) && (*min == 0 || re.is_nullable()),
+            Regex::Intersection(regexs) => regexs.iter().all(Regex::is_nullable),
+            Regex::Complement(re) => !re.is_nullable(),
+            Regex::Trailing(head, tail) => head.is_nullable() && tail.is_nullable(),
+            Regex::Capture(_, re) => re.is_nullable(),
+            Regex::Word(word) => word.is_empty(),
+            Regex::Char(_) | Regex::Class(_) => false,
+            Regex::Empty => true,
+        }
+    }
 }
 pub trait IntoRegex{
     fn into(self) -> Regex;
@@ -117,13 +254,14 @@ impl IntoRegex for RangeInclusive<u8> {
 }
 impl IntoRegex for Vec<u8> {
     fn into(self) -> Regex {
-        if self.len() == 0 {
+        if self.is_empty() {
             return Regex::Empty;
         }
-        let mut bytes = Vec::new();
-        for byte in self {
-            bytes.push(Regex::Char(byte));
-        }
-        Regex::Union(bytes)
+        Regex::one_of(&self)
+    }
+}
+impl IntoRegex for CharClass {
+    fn into(self) -> Regex {
+        Regex::from_class(self)
     }
 }
\ No newline at end of file
@@ -0,0 +1,331 @@
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+
+use crate::lexica::{
+    automata::{LexemeRules, State, TokensDFA, ERROR_STATE},
+    class::CharClass,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+const MAGIC: &[u8; 6] = b"LEXDFA";
+pub const FORMAT_VERSION: u16 = 1;
+const NO_TOKEN: u32 = u32::MAX;
+
+#[derive(Debug)]
+pub enum DFAFileError {
+    Io(io::Error),
+    /// The data doesn't start like a saved DFA.
+    NotADFA,
+    UnsupportedVersion(u16),
+    /// The data was changed or cut after it was saved.
+    Checksum,
+    /// The checksum matches but the content makes no sense, e.g. edges to missing states.
+    Corrupt(&'static str),
+    /// Saving a DFA with a token that isn't in the token list.
+    UnknownToken,
+    /// The DFA was saved with a different token list.
+    TokenCount { saved: u32, given: usize },
+}
+
+impl fmt::Display for DFAFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DFAFileError::Io(error) => write!(f, "{}", error),
+            DFAFileError::NotADFA => write!(f, "not a saved DFA"),
+            DFAFileError::UnsupportedVersion(version) => write!(f, "unsupported DFA format version {}", version),
+            DFAFileError::Checksum => write!(f, "checksum mismatch, the DFA is corrupted"),
+            DFAFileError::Corrupt(reason) => write!(f, "corrupted DFA: {}", reason),
+            DFAFileError::UnknownToken => write!(f, "the DFA has a token missing from the token list"),
+            DFAFileError::TokenCount { saved, given } => write!(f, "the DFA was saved with {} tokens, {} given", saved, given),
+        }
+    }
+}
+
+impl std::error::Error for DFAFileError {}
+
+impl From<io::Error> for DFAFileError {
+    fn from(error: io::Error) -> Self {
+        DFAFileError::Io(error)
+    }
+}
+
+/// Layout, little endian: magic, version, token count, states, class count, the 256 byte
+/// classes, the token id and uses of each state, the lexeme rule index of each state, the
+/// rows, the lexeme rules with their regex trees in prefix order, and an FNV-1a checksum of
+/// everything before it.
+/// Tokens are stored as their index in the token list given to `save`, so `load` needs
+/// the same list.
+impl<T> TokensDFA<T> where T: Eq + Copy + Hash {
+    pub fn save(&self, path: &str, tokens: &[T]) -> Result<(), DFAFileError> {
+        fs::write(path, self.to_bytes(tokens)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str, tokens: &[T]) -> Result<Self, DFAFileError> {
+        Self::from_bytes(&fs::read(path)?, tokens)
+    }
+
+    pub fn to_bytes(&self, tokens: &[T]) -> Result<Vec<u8>, DFAFileError> {
+        let id = |token: &T| tokens.iter().position(|other| other == token).map(|id| id as u32).ok_or(DFAFileError::UnknownToken);
+
+        let mut out = MAGIC.to_vec();
+        out.extend(FORMAT_VERSION.to_le_bytes());
+        out.extend((tokens.len() as u32).to_le_bytes());
+        out.extend(self.states.to_le_bytes());
+        out.extend((self.class_count as u32).to_le_bytes());
+        out.extend(self.classes);
+        for (token, mask) in &self.finals {
+            let token = match token {
+                Some(token) => id(token)?,
+                None => NO_TOKEN,
+            };
+            out.extend(token.to_le_bytes());
+            out.push(*mask as u8);
+        }
+        for rule in &self.rules {
+            out.extend(rule.map_or(NO_TOKEN, |rule| rule as u32).to_le_bytes());
+        }
+        for next in &self.transitions {
+            out.extend(next.to_le_bytes());
+        }
+        out.extend((self.lexeme_rules.regexs.len() as u32).to_le_bytes());
+        for (token, mask, regex) in &self.lexeme_rules.regexs {
+            out.extend(id(token)?.to_le_bytes());
+            out.push(*mask as u8);
+            write_regex(&mut out, regex);
+        }
+        out.extend(checksum(&out).to_le_bytes());
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8], tokens: &[T]) -> Result<Self, DFAFileError> {
+        if bytes.len() < MAGIC.len() + 2 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(DFAFileError::NotADFA);
+        }
+        let version = u16::from_le_bytes([bytes[MAGIC.len()], bytes[MAGIC.len() + 1]]);
+        if version != FORMAT_VERSION {
+            return Err(DFAFileError::UnsupportedVersion(version));
+        }
+        let Some(body_length) = bytes.len().checked_sub(8) else {
+            return Err(DFAFileError::Checksum);
+        };
+        let (body, sum) = bytes.split_at(body_length);
+        if body_length < MAGIC.len() + 2 || checksum(body).to_le_bytes() != sum {
+            return Err(DFAFileError::Checksum);
+        }
+
+        let mut reader = Reader { bytes: body, position: MAGIC.len() + 2 };
+        let saved = reader.u32()?;
+        if saved as usize != tokens.len() {
+            return Err(DFAFileError::TokenCount { saved, given: tokens.len() });
+        }
+        let token = |id: u32| tokens.get(id as usize).copied().ok_or(DFAFileError::Corrupt("token id out of range"));
+
+        let states = reader.u32()?;
+        let class_count = reader.u32()? as usize;
+        if states == 0 || class_count == 0 || class_count > 256 {
+            return Err(DFAFileError::Corrupt("bad state or class count"));
+        }
+        let classes: [u8; 256] = reader.take(256)?.try_into().unwrap();
+        if classes.iter().any(|class| *class as usize >= class_count) {
+            return Err(DFAFileError::Corrupt("byte class out of range"));
+        }
+        let mut finals = Vec::new();
+        for _ in 0..states {
+            let id = reader.u32()?;
+            let mask = reader.mask()?;
+            finals.push((if id == NO_TOKEN { None } else { Some(token(id)?) }, mask));
+        }
+        let mut rules = Vec::new();
+        for _ in 0..states {
+            let rule = reader.u32()?;
+            rules.push(if rule == NO_TOKEN { None } else { Some(rule as usize) });
+        }
+        let mut transitions = Vec::new();
+        for _ in 0..states as usize * class_count {
+            let next = reader.u32()? as State;
+            if next < ERROR_STATE || next >= states as State {
+                return Err(DFAFileError::Corrupt("edge to a missing state"));
+            }
+            transitions.push(next);
+        }
+        let mut regexs = Vec::new();
+        for _ in 0..reader.u32()? {
+            let name = token(reader.u32()?)?;
+            let mask = reader.mask()?;
+            regexs.push((name, mask, reader.regex()?));
+        }
+        if reader.position != body.len() {
+            return Err(DFAFileError::Corrupt("trailing bytes"));
+        }
+        if rules.iter().flatten().any(|rule| *rule >= regexs.len()) {
+            return Err(DFAFileError::Corrupt("lexeme rule index out of range"));
+        }
+
+        Ok(Self {
+            states,
+            finals,
+            classes,
+            class_count,
+            transitions,
+            rules,
+            lexeme_rules: LexemeRules::new(&regexs),
+        })
+    }
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, length: usize) -> Result<&'b [u8], DFAFileError> {
+        let end = self.position.checked_add(length).filter(|end| *end <= self.bytes.len());
+        let Some(end) = end else {
+            return Err(DFAFileError::Corrupt("unexpected end of data"));
+        };
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, DFAFileError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn regexs(&mut self) -> Result<Vec<Regex>, DFAFileError> {
+        (0..self.u32()?).map(|_| self.regex()).collect()
+    }
+
+    fn regex(&mut self) -> Result<Regex, DFAFileError> {
+        Ok(match self.take(1)?[0] {
+            0 => Regex::Concat(self.regexs()?),
+            1 => Regex::Union(self.regexs()?),
+            2 => Regex::Repeat(Box::new(self.regex()?)),
+            3 => {
+                let re = self.regex()?;
+                let min = self.u32()?;
+                let max = match self.take(1)?[0] {
+                    0 => None,
+                    _ => Some(self.u32()?),
+                };
+                Regex::Bounded(Box::new(re), min, max)
+            },
+            4 => Regex::Intersection(self.regexs()?),
+            5 => Regex::Complement(Box::new(self.regex()?)),
+            6 => Regex::Trailing(Box::new(self.regex()?), Box::new(self.regex()?)),
+            7 => {
+                let length = self.u32()? as usize;
+                let name = std::str::from_utf8(self.take(length)?).map_err(|_| DFAFileError::Corrupt("group name is not UTF-8"))?;
+                Regex::Capture(name.to_string(), Box::new(self.regex()?))
+            },
+            8 => {
+                let length = self.u32()? as usize;
+                Regex::Word(self.take(length)?.to_vec())
+            },
+            9 => Regex::Char(self.take(1)?[0]),
+            10 => {
+                let length = self.u32()? as usize;
+                let bounds = self.take(2 * length)?;
+                Regex::Class(CharClass::from_ranges(bounds.chunks(2).map(|range| range[0]..=range[1])))
+            },
+            11 => Regex::Empty,
+            _ => return Err(DFAFileError::Corrupt("unknown regex node")),
+        })
+    }
+
+    fn mask(&mut self) -> Result<TokenUses, DFAFileError> {
+        Ok(match self.take(1)?[0] {
+            1 => TokenUses::Default,
+            2 => TokenUses::GetLexeme,
+            3 => TokenUses::IgnoreThis,
+            4 => TokenUses::InitBlockComment,
+            5 => TokenUses::EndBlockComment,
+            6 => TokenUses::InitInlineComment,
+            _ => return Err(DFAFileError::Corrupt("unknown token use")),
+        })
+    }
+}
+
+fn write_regexs(out: &mut Vec<u8>, regexs: &[Regex]) {
+    out.extend((regexs.len() as u32).to_le_bytes());
+    for regex in regexs {
+        write_regex(out, regex);
+    }
+}
+
+/// A tag byte for the variant, followed by its fields.
+fn write_regex(out: &mut Vec<u8>, regex: &Regex) {
+    match regex {
+        Regex::Concat(regexs) => {
+            out.push(0);
+            write_regexs(out, regexs);
+        },
+        Regex::Union(regexs) => {
+            out.push(1);
+            write_regexs(out, regexs);
+        },
+        Regex::Repeat(re) => {
+            out.push(2);
+            write_regex(out, re);
+        },
+        Regex::Bounded(re, min, max) => {
+            out.push(3);
+            write_regex(out, re);
+            out.extend(min.to_le_bytes());
+            match max {
+                Some(max) => {
+                    out.push(1);
+                    out.extend(max.to_le_bytes());
+                },
+                None => out.push(0),
+            }
+        },
+        Regex::Intersection(regexs) => {
+            out.push(4);
+            write_regexs(out, regexs);
+        },
+        Regex::Complement(re) => {
+            out.push(5);
+            write_regex(out, re);
+        },
+        Regex::Trailing(head, tail) => {
+            out.push(6);
+            write_regex(out, head);
+            write_regex(out, tail);
+        },
+        Regex::Capture(name, re) => {
+            out.push(7);
+            out.extend((name.len() as u32).to_le_bytes());
+            out.extend(name.as_bytes());
+            write_regex(out, re);
+        },
+        Regex::Word(word) => {
+            out.push(8);
+            out.extend((word.len() as u32).to_le_bytes());
+            out.extend(word);
+        },
+        Regex::Char(c) => {
+            out.push(9);
+            out.push(*c);
+        },
+        Regex::Class(class) => {
+            out.push(10);
+            out.extend((class.ranges().count() as u32).to_le_bytes());
+            for range in class.ranges() {
+                out.push(*range.start());
+                out.push(*range.end());
+            }
+        },
+        Regex::Empty => out.push(11),
+    }
+}
+
+/// 64 bit FNV-1a.
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01B3))
+}
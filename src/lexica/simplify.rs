@@ -0,0 +1,240 @@
+use crate::lexica::{class::CharClass, regex::Regex};
+
+impl Regex {
+    /// Rewrites the regex into an equivalent and smaller tree.
+    ///
+    /// Nested concatenations and unions are flattened, adjacent bytes become words,
+    /// single byte alternatives are merged into one class, duplicated alternatives are
+    /// removed, repetitions of repetitions are collapsed and double complements cancel.
+    pub fn simplify(self) -> Regex {
+        match self {
+            Regex::Concat(regexs) => simplify_concat(regexs),
+            Regex::Union(regexs) => simplify_union(regexs),
+            Regex::Repeat(re) => repeat(re.simplify()),
+            Regex::Bounded(re, min, max) => bounded(re.simplify(), min, max),
+            Regex::Intersection(regexs) => simplify_intersection(regexs),
+            Regex::Complement(re) => complement(re.simplify()),
+            Regex::Trailing(head, tail) => Regex::Trailing(Box::new(head.simplify()), Box::new(tail.simplify())),
+            Regex::Capture(name, re) => Regex::Capture(name, Box::new(re.simplify())),
+            Regex::Word(word) => literal(word),
+            Regex::Char(c) => Regex::Char(c),
+            Regex::Class(class) => class_or_char(class),
+            Regex::Empty => Regex::Empty,
+        }
+    }
+
+    pub fn is_nothing(&self) -> bool {
+        match self {
+            Regex::Class(class) => class.is_empty(),
+            Regex::Union(regexs) => regexs.iter().all(Regex::is_nothing),
+            Regex::Concat(regexs) | Regex::Intersection(regexs) => regexs.iter().any(Regex::is_nothing),
+            Regex::Complement(re) => re.is_everything(),
+            Regex::Trailing(head, tail) => head.is_nothing() || tail.is_nothing(),
+            Regex::Capture(_, re) => re.is_nothing(),
+            _ => false,
+        }
+    }
+
+    /// Whether the regex obviously matches every string, like `[\x00-\xFF]*`.
+    fn is_everything(&self) -> bool {
+        match self {
+            Regex::Repeat(re) | Regex::Bounded(re, 0, None) => matches!(&**re, Regex::Class(class) if *class == CharClass::full()),
+            Regex::Union(regexs) => regexs.iter().any(Regex::is_everything),
+            Regex::Intersection(regexs) => !regexs.is_empty() && regexs.iter().all(Regex::is_everything),
+            Regex::Capture(_, re) => re.is_everything(),
+            Regex::Complement(re) => re.is_nothing(),
+            _ => false,
+        }
+    }
+}
+
+fn simplify_concat(regexs: Vec<Regex>) -> Regex {
+    let mut items: Vec<Regex> = Vec::new();
+    for re in regexs {
+        match re.simplify() {
+            Regex::Empty => {},
+            Regex::Concat(inner) => {
+                for item in inner {
+                    push_concat_item(&mut items, item);
+                }
+            },
+            re if re.is_nothing() => return Regex::nothing(),
+            re => push_concat_item(&mut items, re),
+        }
+    }
+    match items.len() {
+        0 => Regex::Empty,
+        1 => items.pop().unwrap(),
+        _ => Regex::Concat(items),
+    }
+}
+
+fn push_concat_item(items: &mut Vec<Regex>, item: Regex) {
+    let Some(last) = items.last_mut() else {
+        items.push(item);
+        return;
+    };
+
+    if let (Some(mut bytes), Some(other)) = (literal_bytes(last), literal_bytes(&item)) {
+        bytes.extend(other);
+        *last = literal(bytes);
+        return;
+    }
+    match (&*last, &item) {
+        (Regex::Repeat(a), Regex::Repeat(b)) if a == b => {},
+        (a, Regex::Repeat(b)) if a == &**b => {
+            let re = std::mem::replace(last, Regex::Empty);
+            *last = Regex::Bounded(Box::new(re), 1, None);
+        },
+        (Regex::Repeat(a), b) if &**a == b => {
+            *last = Regex::Bounded(Box::new(item), 1, None);
+        },
+        _ => items.push(item),
+    }
+}
+
+fn simplify_union(regexs: Vec<Regex>) -> Regex {
+    let mut branches: Vec<Regex> = Vec::new();
+    let mut class: Option<(usize, CharClass)> = None;
+    let mut has_empty = false;
+
+    let mut pending: Vec<Regex> = regexs.into_iter().rev().map(Regex::simplify).collect();
+    while let Some(branch) = pending.pop() {
+        match branch {
+            Regex::Union(inner) => pending.extend(inner.into_iter().rev()),
+            Regex::Empty => has_empty = true,
+            Regex::Char(c) => merge_class(&mut class, branches.len(), CharClass::from_bytes(&[c])),
+            Regex::Class(other) => merge_class(&mut class, branches.len(), other),
+            Regex::Bounded(re, 0, Some(1)) => {
+                has_empty = true;
+                pending.push(*re);
+            },
+            branch => {
+                if !branches.contains(&branch) {
+                    branches.push(branch);
+                }
+            },
+        }
+    }
+
+    if let Some((index, class)) = class {
+        if !class.is_empty() {
+            branches.insert(index, class_or_char(class));
+        }
+    }
+    let union = match branches.len() {
+        0 if has_empty => return Regex::Empty,
+        0 => return Regex::nothing(),
+        1 => branches.pop().unwrap(),
+        _ => Regex::Union(branches),
+    };
+    if has_empty && !union.is_nullable() {
+        return bounded(union, 0, Some(1));
+    }
+    union
+}
+
+fn merge_class(class: &mut Option<(usize, CharClass)>, index: usize, other: CharClass) {
+    match class {
+        Some((_, current)) => *current = current.union(&other),
+        None => *class = Some((index, other)),
+    }
+}
+
+fn simplify_intersection(regexs: Vec<Regex>) -> Regex {
+    let mut branches: Vec<Regex> = Vec::new();
+    let mut pending: Vec<Regex> = regexs.into_iter().rev().map(Regex::simplify).collect();
+    while let Some(branch) = pending.pop() {
+        match branch {
+            Regex::Intersection(inner) => pending.extend(inner.into_iter().rev()),
+            branch if branch.is_nothing() => return Regex::nothing(),
+            Regex::Complement(inner) if inner.is_nothing() => {},
+            branch => {
+                if !branches.contains(&branch) {
+                    branches.push(branch);
+                }
+            },
+        }
+    }
+
+    let classes: Option<Vec<CharClass>> = branches.iter().map(|branch| match branch {
+        Regex::Char(c) => Some(CharClass::from_bytes(&[*c])),
+        Regex::Class(class) => Some(class.clone()),
+        _ => None,
+    }).collect();
+    if let Some(classes) = classes.filter(|classes| !classes.is_empty()) {
+        let class = classes.iter().fold(CharClass::full(), |acc, class| acc.intersection(class));
+        return class_or_char(class);
+    }
+
+    match branches.len() {
+        0 => complement(Regex::nothing()),
+        1 => branches.pop().unwrap(),
+        _ => Regex::Intersection(branches),
+    }
+}
+
+fn complement(re: Regex) -> Regex {
+    match re {
+        Regex::Complement(inner) => *inner,
+        re => Regex::Complement(Box::new(re)),
+    }
+}
+
+fn repeat(re: Regex) -> Regex {
+    match re {
+        Regex::Empty => Regex::Empty,
+        re if re.is_nothing() => Regex::Empty,
+        Regex::Repeat(inner) => Regex::Repeat(inner),
+        Regex::Bounded(inner, 0 | 1, _) => Regex::Repeat(inner),
+        re => Regex::Repeat(Box::new(re)),
+    }
+}
+
+fn bounded(re: Regex, min: u32, max: Option<u32>) -> Regex {
+    if max.is_some_and(|max| max < min) {
+        return Regex::nothing();
+    }
+    if max == Some(0) || re == Regex::Empty {
+        return Regex::Empty;
+    }
+    if re.is_nothing() {
+        return if min == 0 { Regex::Empty } else { Regex::nothing() };
+    }
+    match (min, max) {
+        (1, Some(1)) => return re,
+        (0, None) => return repeat(re),
+        _ => {},
+    }
+    match re {
+        Regex::Repeat(inner) => Regex::Repeat(inner),
+        Regex::Bounded(inner, 0, Some(1)) if min == 0 && max == Some(1) => Regex::Bounded(inner, 0, Some(1)),
+        Regex::Bounded(inner, inner_min, inner_max) if inner_min <= 1 && min <= 1 && (inner_max.is_none() || max.is_none()) => {
+            bounded(*inner, inner_min * min, None)
+        },
+        re => Regex::Bounded(Box::new(re), min, max),
+    }
+}
+
+fn class_or_char(class: CharClass) -> Regex {
+    if class.len() == 1 {
+        return Regex::Char(class.bytes().next().unwrap());
+    }
+    Regex::Class(class)
+}
+
+fn literal(bytes: Vec<u8>) -> Regex {
+    match bytes.len() {
+        0 => Regex::Empty,
+        1 => Regex::Char(bytes[0]),
+        _ => Regex::Word(bytes),
+    }
+}
+
+fn literal_bytes(regex: &Regex) -> Option<Vec<u8>> {
+    match regex {
+        Regex::Char(c) => Some(vec![*c]),
+        Regex::Word(word) => Some(word.clone()),
+        _ => None,
+    }
+}
@@ -1,6 +1,7 @@
 use std::hash::Hash;
+use std::ops::Range;
 
-#[derive (Clone, Copy, PartialEq, Eq)]
+#[derive (Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenUses {
     Default = 1,
     GetLexeme =  2,
@@ -16,5 +17,19 @@ pub struct Token<T>
     where T: Eq + Copy + Hash
 {
     pub t_type: T,
-    pub t_name: Option<String>
+    /// Lexeme of `TokenUses::GetLexeme` tokens, bytes that are not UTF-8 read as U+FFFD.
+    pub t_name: Option<String>,
+    pub captures: Vec<(String, Range<usize>)>
+}
+
+impl<T> Token<T> where T: Eq + Copy + Hash {
+    pub fn capture_span(&self, name: &str) -> Option<Range<usize>> {
+        self.captures.iter().find(|(other, _)| other == name).map(|(_, span)| span.clone())
+    }
+
+    /// Text of a named group, only available for `TokenUses::GetLexeme` tokens.
+    pub fn capture(&self, name: &str) -> Option<&str> {
+        let span = self.capture_span(name)?;
+        self.t_name.as_ref()?.get(span)
+    }
 }
\ No newline at end of file
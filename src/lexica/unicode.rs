@@ -0,0 +1,214 @@
+use std::ops::RangeInclusive;
+
+use crate::lexica::{class::CharClass, regex::{IntoRegex, Regex}, unicode_tables};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeCategory {
+    Letter,
+    Digit,
+    XidStart,
+    XidContinue
+}
+
+impl UnicodeCategory {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "L" | "Letter" => Some(UnicodeCategory::Letter),
+            "Nd" | "Digit" => Some(UnicodeCategory::Digit),
+            "XID_Start" | "XIDS" => Some(UnicodeCategory::XidStart),
+            "XID_Continue" | "XIDC" => Some(UnicodeCategory::XidContinue),
+            _ => None
+        }
+    }
+
+    pub fn ranges(self) -> &'static [(u32, u32)] {
+        match self {
+            UnicodeCategory::Letter => unicode_tables::LETTER,
+            UnicodeCategory::Digit => unicode_tables::DIGIT,
+            UnicodeCategory::XidStart => unicode_tables::XID_START,
+            UnicodeCategory::XidContinue => unicode_tables::XID_CONTINUE,
+        }
+    }
+
+    pub fn contains(self, c: char) -> bool {
+        let c = c as u32;
+        self.ranges().binary_search_by(|(low, high)| {
+            if *high < c {
+                std::cmp::Ordering::Less
+            } else if *low > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        }).is_ok()
+    }
+}
+
+impl Regex {
+    /// Matches the UTF-8 encoding of any code point in `range`.
+    pub fn from_chars(range: RangeInclusive<char>) -> Regex {
+        Regex::from_char_ranges([range])
+    }
+
+    pub fn from_char_ranges<I>(ranges: I) -> Regex
+        where I: IntoIterator<Item = RangeInclusive<char>> {
+        let ranges: Vec<(u32, u32)> = ranges.into_iter()
+            .map(|range| (*range.start() as u32, *range.end() as u32))
+            .collect();
+        Regex::from_code_points(&ranges)
+    }
+
+    pub fn unicode(category: UnicodeCategory) -> Regex {
+        Regex::from_code_points(category.ranges())
+    }
+
+    /// Builds the byte level automaton for a set of code point ranges. Surrogates are skipped.
+    pub fn from_code_points(ranges: &[(u32, u32)]) -> Regex {
+        let mut ascii = CharClass::new();
+        let mut trie = Utf8Trie::default();
+        for (start, end) in normalize_code_points(ranges) {
+            let mut sequences = Vec::new();
+            utf8_sequences(start, end, &mut sequences);
+            for sequence in sequences {
+                if sequence.len() == 1 {
+                    ascii = ascii.union(&CharClass::from_range(sequence[0].0..=sequence[0].1));
+                } else {
+                    trie.insert(&sequence);
+                }
+            }
+        }
+
+        let mut branches = Vec::new();
+        if !ascii.is_empty() {
+            branches.push(Regex::Class(ascii));
+        }
+        if let Regex::Union(multibyte) = trie.into_regex() {
+            branches.extend(multibyte);
+        }
+        if branches.len() == 1 {
+            return branches.pop().unwrap();
+        }
+        Regex::Union(branches)
+    }
+}
+
+impl IntoRegex for RangeInclusive<char> {
+    fn into(self) -> Regex {
+        Regex::from_chars(self)
+    }
+}
+
+pub fn normalize_code_points(ranges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut sorted: Vec<(u32, u32)> = ranges.iter()
+        .filter(|(start, end)| start <= end)
+        .map(|(start, end)| (*start, (*end).min(char::MAX as u32)))
+        .collect();
+    sorted.sort();
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        if let Some(last) = merged.last_mut() {
+            if start <= last.1 + 1 {
+                last.1 = last.1.max(end);
+                continue;
+            }
+        }
+        merged.push((start, end));
+    }
+    merged
+}
+
+pub fn negate_code_points(ranges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut negated = Vec::new();
+    let mut next = 0;
+    for (start, end) in normalize_code_points(ranges) {
+        if start > next {
+            negated.push((next, start - 1));
+        }
+        next = end + 1;
+    }
+    if next <= char::MAX as u32 {
+        negated.push((next, char::MAX as u32));
+    }
+    negated
+}
+
+fn utf8_sequences(start: u32, end: u32, out: &mut Vec<Vec<(u8, u8)>>) {
+    if start <= 0xDFFF && end >= 0xD800 {
+        if start < 0xD800 {
+            utf8_sequences(start, 0xD7FF, out);
+        }
+        if end > 0xDFFF {
+            utf8_sequences(0xE000, end, out);
+        }
+        return;
+    }
+    for boundary in [0x7F, 0x7FF, 0xFFFF] {
+        if start <= boundary && end > boundary {
+            utf8_sequences(start, boundary, out);
+            utf8_sequences(boundary + 1, end, out);
+            return;
+        }
+    }
+
+    let length = encode(start).len();
+    for i in 1..length {
+        let max = (1u32 << (6 * i)) - 1;
+        if start & !max != end & !max {
+            if start & max != 0 {
+                utf8_sequences(start, start | max, out);
+                utf8_sequences((start | max) + 1, end, out);
+                return;
+            }
+            if end & max != max {
+                utf8_sequences(start, (end & !max) - 1, out);
+                utf8_sequences(end & !max, end, out);
+                return;
+            }
+        }
+    }
+
+    let low = encode(start);
+    let high = encode(end);
+    out.push(low.into_iter().zip(high).collect());
+}
+
+fn encode(code_point: u32) -> Vec<u8> {
+    let mut buf = [0; 4];
+    char::from_u32(code_point).unwrap().encode_utf8(&mut buf).as_bytes().to_vec()
+}
+
+#[derive(Default)]
+struct Utf8Trie {
+    edges: Vec<((u8, u8), Utf8Trie)>
+}
+
+impl Utf8Trie {
+    fn insert(&mut self, sequence: &[(u8, u8)]) {
+        let Some((first, rest)) = sequence.split_first() else {
+            return;
+        };
+        if let Some((range, child)) = self.edges.last_mut() {
+            if range == first {
+                child.insert(rest);
+                return;
+            }
+        }
+        let mut child = Utf8Trie::default();
+        child.insert(rest);
+        self.edges.push((*first, child));
+    }
+
+    fn into_regex(self) -> Regex {
+        if self.edges.is_empty() {
+            return Regex::Empty;
+        }
+        let branches = self.edges.into_iter().map(|((low, high), child)| {
+            let class = Regex::Class(CharClass::from_range(low..=high));
+            match child.into_regex() {
+                Regex::Empty => class,
+                rest => Regex::Concat(vec![class, rest]),
+            }
+        }).collect();
+        Regex::Union(branches)
+    }
+}
@@ -1,4 +1,5 @@
 use compiler_create::lexica::{
+    definitions::RegexDefinitions,
     regex::Regex,
     tokens::TokenUses
 };
@@ -34,9 +35,10 @@ impl IsTerminal for Vars {
 }
 
 fn main() {
-    let letter = Regex::new(b'a'..=b'z') + Regex::new(b'A'..=b'Z');
-    let digit  = Regex::new(b'0'..=b'9');
-    let digits = Regex::new(b'0'..=b'9') | Regex::repeat(Regex::new(b'0'..=b'9'));
+    let mut definitions = RegexDefinitions::new();
+    definitions.define_pattern("LETTER", "[a-zA-Z]");
+    definitions.define_pattern("DIGIT", "[0-9]");
+    definitions.define_pattern("DIGITS", "{DIGIT}+");
 
     let tokens_regexs = vec![  
         (Vars::If   , TokenUses::Default, Regex::new("if")),
@@ -45,11 +47,11 @@ fn main() {
         (Vars::Let  , TokenUses::Default, Regex::new("let")),
         (Vars::Set  , TokenUses::Default, Regex::new("=")),
 
-        (Vars::Id, TokenUses::GetLexeme, letter.clone()|Regex::repeat(letter.clone() + digit.clone())),
+        (Vars::Id, TokenUses::GetLexeme, definitions.parse("{LETTER}({LETTER}|{DIGIT})*").unwrap()),
 
-        (Vars::ConstInt    , TokenUses::GetLexeme  , digits.clone()),
-        (Vars::ConstFloat  , TokenUses::GetLexeme  , digits.clone()|Regex::new(b'.')|digits.clone()),
-        (Vars::ConstChar   , TokenUses::GetLexeme  , Regex::new(b'\'')|Regex::any()|Regex::new(b'\'')),
+        (Vars::ConstInt    , TokenUses::GetLexeme  , definitions.get("DIGITS").unwrap()),
+        (Vars::ConstFloat  , TokenUses::GetLexeme  , definitions.parse("(?<int>{DIGITS})\\.(?<frac>{DIGITS})").unwrap()),
+        (Vars::ConstChar   , TokenUses::GetLexeme  , Regex::new(b'\'')|Regex::capture("char", Regex::not_in(b"\\'\n") + (Regex::new(b'\\')|Regex::any()))|Regex::new(b'\'')),
         
         (Vars::Add, TokenUses::Default, Regex::new(b'+')),
         (Vars::Sub, TokenUses::Default, Regex::new(b'-')),
@@ -0,0 +1,108 @@
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::{Construction, DFABuilder, TokensDFA, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    ConstInt, ConstFloat, ConstChar, Ws,
+}
+
+fn tokens() -> Vec<(Kind, TokenUses, Regex)> {
+    vec![
+        (Kind::ConstInt, TokenUses::GetLexeme, Regex::parse("[0-9]+").unwrap()),
+        (Kind::ConstFloat, TokenUses::GetLexeme, Regex::parse("(?<int>[0-9]+)\\.(?<frac>[0-9]+)").unwrap()),
+        (Kind::ConstChar, TokenUses::GetLexeme, Regex::parse("'(?<char>[^\\\\'\\n]|\\\\.)'").unwrap()),
+        (Kind::Ws, TokenUses::IgnoreThis, Regex::new(vec![b' ', b'\n'])),
+    ]
+}
+
+fn spans(regex: &str, input: &str) -> Option<Vec<(String, std::ops::Range<usize>)>> {
+    Regex::parse(regex).unwrap().captures(input.as_bytes())
+}
+
+#[test]
+fn parses_and_displays_named_groups() {
+    let regex = Regex::parse("(?<int>[0-9]+)(?P<frac>\\.[0-9]+)?").unwrap();
+    assert_eq!(regex.to_string(), "(?<int>[0-9]+)(?<frac>\\.[0-9]+)?");
+    assert_eq!(Regex::parse(&regex.to_string()).unwrap(), regex);
+    assert!(Regex::parse("(?<>a)").is_err());
+    assert!(Regex::parse("(?a)").is_err());
+}
+
+#[test]
+fn replay_reports_group_spans() {
+    assert_eq!(spans("(?<int>[0-9]+)\\.(?<frac>[0-9]+)", "12.50"), Some(vec![
+        ("int".to_string(), 0..2),
+        ("frac".to_string(), 3..5),
+    ]));
+    assert_eq!(spans("(?<int>[0-9]+)(\\.(?<frac>[0-9]+))?", "12"), Some(vec![("int".to_string(), 0..2)]));
+    assert_eq!(spans("((?<item>[a-z]+),)*", "ab,c,"), Some(vec![("item".to_string(), 3..4)]));
+    assert_eq!(spans("(?<first>a*)(?<second>a*)", "aaa"), Some(vec![
+        ("first".to_string(), 0..3),
+        ("second".to_string(), 3..3),
+    ]));
+    assert_eq!(spans("(?<int>[0-9]+)", "12a"), None);
+}
+
+#[test]
+fn groups_do_not_change_the_language() {
+    for construction in [Construction::Subset, Construction::Derivatives] {
+        let dfa = DFABuilder::new().construction(construction).build(tokens());
+        assert_eq!(dfa.test_string("3.14".to_string()), Some(Kind::ConstFloat));
+        assert_eq!(dfa.test_string("'\\n'".to_string()), Some(Kind::ConstChar));
+        assert_eq!(dfa.test_string("314".to_string()), Some(Kind::ConstInt));
+    }
+}
+
+#[test]
+fn lexer_reports_captures_on_tokens() {
+    let path = std::env::temp_dir().join("compiler_create_captures.txt");
+    fs::write(&path, "3.14 'x' '\\'' 42\n").unwrap();
+    let tokens: Vec<_> = LexicalAnalysis::new(tokens(), path.to_str().unwrap()).collect();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(tokens[0].t_type, Kind::ConstFloat);
+    assert_eq!(tokens[0].capture("int"), Some("3"));
+    assert_eq!(tokens[0].capture("frac"), Some("14"));
+    assert_eq!(tokens[0].capture_span("frac"), Some(2..4));
+    assert_eq!(tokens[1].capture("char"), Some("x"));
+    assert_eq!(tokens[2].capture("char"), Some("\\'"));
+    assert_eq!(tokens[3].t_type, Kind::ConstInt);
+    assert!(tokens[3].captures.is_empty());
+    assert_eq!(tokens[3].capture("int"), None);
+}
+
+#[test]
+fn each_declaration_replays_its_own_groups() {
+    let tokens = || vec![
+        (Kind::ConstInt, TokenUses::GetLexeme, Regex::parse("0x(?<hex>[0-9a-f]+)").unwrap()),
+        (Kind::ConstInt, TokenUses::GetLexeme, Regex::parse("(?<dec>[0-9]+)").unwrap()),
+        (Kind::Ws, TokenUses::IgnoreThis, Regex::new(vec![b' ', b'\n'])),
+    ];
+    let dfa = TokensDFA::new(tokens());
+    let captures = |lexeme: &[u8]| {
+        let state = lexeme.iter().fold(INIT_STATE, |state, byte| dfa.next(state, *byte));
+        dfa.captures(dfa.rules[state as usize], lexeme)
+    };
+    assert_eq!(captures(b"0x1f"), vec![("hex".to_string(), 2..4)]);
+    assert_eq!(captures(b"42"), vec![("dec".to_string(), 0..2)]);
+
+    let path = std::env::temp_dir().join("compiler_create_captures_declarations.txt");
+    fs::write(&path, "0x1f 42\n").unwrap();
+    for backend in [Backend::TokensDFA, Backend::PikeVM, Backend::CompressedDFA] {
+        let lexed: Vec<_> = LexicalAnalysis::with_backend(tokens(), path.to_str().unwrap(), backend)
+            .map(|token| token.captures)
+            .collect();
+        assert_eq!(lexed, vec![
+            vec![("hex".to_string(), 2..4)],
+            vec![("dec".to_string(), 0..2)],
+        ], "{:?}", backend);
+    }
+    fs::remove_file(&path).unwrap();
+}
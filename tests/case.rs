@@ -0,0 +1,95 @@
+use compiler_create::lexica::{
+    automata::TokensDFA,
+    class::CharClass,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+fn single(regex: Regex) -> TokensDFA<u8> {
+    TokensDFA::new(vec![(0, TokenUses::Default, regex)])
+}
+
+fn accepts(dfa: &TokensDFA<u8>, input: &str) -> bool {
+    dfa.test_string(input.to_string()) == Some(0)
+}
+
+fn class(regex: Regex) -> CharClass {
+    match regex {
+        Regex::Class(class) => class,
+        _ => panic!("not a class"),
+    }
+}
+
+#[test]
+fn mixed_case_words_match_any_case() {
+    let dfa = single(Regex::word_ci("SeLeCt"));
+    for input in ["select", "SELECT", "SeLeCt", "sELECt"] {
+        assert!(accepts(&dfa, input), "{}", input);
+    }
+    for input in ["selec", "selects", "se1ect", "SELECT "] {
+        assert!(!accepts(&dfa, input), "{}", input);
+    }
+
+    // Only letters fold, the rest of the word is kept as is.
+    let dfa = single(Regex::word_ci("Do_Loop2"));
+    assert!(accepts(&dfa, "do_loop2") && accepts(&dfa, "DO_LOOP2"));
+    assert!(!accepts(&dfa, "do-loop2"));
+
+    let dfa = single(Regex::word_ci("Ärger"));
+    assert!(accepts(&dfa, "ärger") && accepts(&dfa, "ÄRGER") && accepts(&dfa, "äRGER"));
+}
+
+#[test]
+fn keywords_over_identifiers_ignore_case() {
+    let dfa = TokensDFA::new(vec![
+        (0, TokenUses::Default, Regex::word_ci("begin")),
+        (1, TokenUses::Default, Regex::new("end").ignore_case()),
+        (2, TokenUses::GetLexeme, Regex::parse("[a-zA-Z]+").unwrap()),
+    ]);
+    assert_eq!(dfa.test_string("BEGIN".to_string()), Some(0));
+    assert_eq!(dfa.test_string("End".to_string()), Some(1));
+    assert_eq!(dfa.test_string("Ending".to_string()), Some(2));
+}
+
+#[test]
+fn ranges_across_letters_and_symbols_fold_only_the_letters() {
+    let folded = class(Regex::parse("[Z-a]").unwrap().ignore_case());
+    assert_eq!(folded, CharClass::from_ranges(vec![b'A'..=b'A', b'Z'..=b'a', b'z'..=b'z']));
+
+    let folded = class(Regex::parse("[@-C]").unwrap().ignore_case());
+    assert_eq!(folded, CharClass::from_ranges(vec![b'@'..=b'C', b'a'..=b'c']));
+
+    let dfa = single(Regex::plus(Regex::parse("[0-9_-z]").unwrap().ignore_case()));
+    assert!(accepts(&dfa, "09_`azAZ"));
+    assert!(!accepts(&dfa, "{") && !accepts(&dfa, "@") && !accepts(&dfa, "["));
+}
+
+#[test]
+fn folding_twice_changes_nothing() {
+    let already_folded = class(Regex::parse("[a-zA-Z_]").unwrap());
+    assert_eq!(class(Regex::Class(already_folded.clone()).ignore_case()), already_folded);
+
+    for pattern in ["[a-zA-Z_][a-zA-Z0-9_]*", "[Z-a]x", "while|(do)+", "[^0-9]", "(é|ß)?x"] {
+        let once = Regex::parse(pattern).unwrap().ignore_case();
+        let twice = single(once.clone().ignore_case());
+        assert_eq!(single(once).transitions, twice.transitions, "{}", pattern);
+    }
+}
+
+#[test]
+fn nested_regexes_are_folded_everywhere() {
+    let regex = Regex::repeat(Regex::new("ab") + Regex::optional(Regex::new(b'c'))) | Regex::new(b';');
+    let dfa = single(regex.ignore_case());
+    assert!(accepts(&dfa, "aBAbcC;"));
+    assert!(accepts(&dfa, ";"));
+    assert!(!accepts(&dfa, "aBd;"));
+
+    // Words that are not UTF-8 fold byte by byte.
+    match Regex::Word(vec![0xFF, b'x']).ignore_case() {
+        Regex::Concat(items) => match items.as_slice() {
+            [Regex::Char(0xFF), Regex::Class(class)] => assert_eq!(*class, CharClass::from_bytes(b"xX")),
+            _ => panic!("{} items", items.len()),
+        },
+        _ => panic!("not a concatenation"),
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    class::CharClass,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+fn ranges(class: &CharClass) -> Vec<(u8, u8)> {
+    class.ranges().map(|range| (*range.start(), *range.end())).collect()
+}
+
+#[test]
+fn adjacent_and_overlapping_ranges_merge() {
+    assert_eq!(ranges(&CharClass::from_ranges(vec![b'a'..=b'f', b'g'..=b'z'])), vec![(b'a', b'z')]);
+    assert_eq!(ranges(&CharClass::from_ranges(vec![b'm'..=b'z', b'a'..=b'p', b'0'..=b'9'])), vec![(b'0', b'9'), (b'a', b'z')]);
+    assert_eq!(ranges(&CharClass::from_bytes(&[3, 1, 2, 5])), vec![(1, 3), (5, 5)]);
+    assert_eq!(ranges(&CharClass::from_ranges(vec![0..=0, 1..=254, 255..=255])), vec![(0, 255)]);
+    assert_eq!(CharClass::from_ranges(vec![0..=127, 128..=255]), CharClass::full());
+    // Empty ranges are dropped.
+    let (start, end) = (b'z', b'a');
+    assert!(CharClass::from_ranges(vec![start..=end]).is_empty());
+}
+
+#[test]
+fn union_merges_adjacent_ranges() {
+    let low = CharClass::from_range(0..=b'`');
+    let high = CharClass::from_range(b'a'..=255);
+    assert_eq!(low.union(&high), CharClass::full());
+    assert_eq!(CharClass::from_range(b'a'..=b'c').union(&CharClass::from_range(b'e'..=b'g')).len(), 6);
+    assert_eq!(
+        ranges(&CharClass::from_range(b'a'..=b'c').union(&CharClass::from_range(b'd'..=b'd'))),
+        vec![(b'a', b'd')]
+    );
+    assert_eq!(CharClass::new().union(&CharClass::from_bytes(&[0, 255])), CharClass::from_bytes(&[255, 0]));
+}
+
+#[test]
+fn intersection_keeps_boundary_bytes() {
+    let ends = CharClass::from_bytes(&[0, 255]);
+    assert_eq!(ends.intersection(&CharClass::full()), ends);
+    assert_eq!(ranges(&CharClass::from_range(0..=10).intersection(&CharClass::from_range(10..=255))), vec![(10, 10)]);
+    assert!(CharClass::from_range(0..=9).intersection(&CharClass::from_range(10..=255)).is_empty());
+    assert!(ends.intersection(&CharClass::new()).is_empty());
+    assert_eq!(
+        ranges(&CharClass::from_ranges(vec![0..=5, 250..=255]).intersection(&CharClass::from_ranges(vec![5..=250]))),
+        vec![(5, 5), (250, 250)]
+    );
+}
+
+#[test]
+fn negation_covers_the_boundaries() {
+    assert_eq!(CharClass::new().negate(), CharClass::full());
+    assert!(CharClass::full().negate().is_empty());
+    assert_eq!(ranges(&CharClass::from_bytes(&[0]).negate()), vec![(1, 255)]);
+    assert_eq!(ranges(&CharClass::from_bytes(&[255]).negate()), vec![(0, 254)]);
+    assert_eq!(ranges(&CharClass::from_bytes(&[0, 255]).negate()), vec![(1, 254)]);
+    assert_eq!(ranges(&CharClass::from_ranges(vec![1..=254]).negate()), vec![(0, 0), (255, 255)]);
+    assert_eq!(ranges(&CharClass::from_ranges(vec![b'a'..=b'z', b'A'..=b'Z']).negate()), vec![(0, b'@'), (b'[', b'`'), (b'{', 255)]);
+}
+
+/// Every operation agrees with the same one done byte by byte.
+#[test]
+fn operations_match_byte_sets() {
+    let classes = [
+        CharClass::new(),
+        CharClass::full(),
+        CharClass::from_bytes(&[0, 1, 2, 127, 128, 254, 255]),
+        CharClass::from_ranges(vec![0..=31, 48..=57, 200..=255]),
+        CharClass::from_ranges(vec![32..=47, 58..=199]),
+        CharClass::from_ranges((0..=255u8).step_by(3).map(|byte| byte..=byte)),
+    ];
+    for class in &classes {
+        assert_eq!(class.len(), class.bytes().count());
+        let negated = class.negate();
+        assert_eq!(negated.negate(), *class);
+        for byte in 0..=255u8 {
+            assert_eq!(negated.contains(byte), !class.contains(byte));
+        }
+        for other in &classes {
+            let (union, intersection) = (class.union(other), class.intersection(other));
+            assert_eq!(union, other.union(class));
+            assert_eq!(intersection, other.intersection(class));
+            for byte in 0..=255u8 {
+                assert_eq!(union.contains(byte), class.contains(byte) || other.contains(byte));
+                assert_eq!(intersection.contains(byte), class.contains(byte) && other.contains(byte));
+            }
+        }
+    }
+}
+
+/// A negated class matches every byte, the lexer must not read one past the end of the input.
+#[test]
+fn negated_classes_end_at_the_end_of_input() {
+    let tokens = vec![
+        (0, TokenUses::GetLexeme, Regex::plus(Regex::not_in(b" "))),
+        (1, TokenUses::IgnoreThis, Regex::new(b' ')),
+    ];
+    for (source, words) in [
+        ("ab cd", vec!["ab", "cd"]),
+        ("x", vec!["x"]),
+        ("é ÿ", vec!["é", "ÿ"]),
+        ("ab cd ", vec!["ab", "cd"]),
+    ] {
+        let path = std::env::temp_dir().join(format!("compiler_create_negated_{}.txt", source.len()));
+        fs::write(&path, source).unwrap();
+        let lexed: Vec<_> = LexicalAnalysis::new(tokens.clone(), path.to_str().unwrap())
+            .map(|token| token.t_name.unwrap())
+            .collect();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(lexed, words, "{:?}", source);
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, TokensDFA},
+    pike::PikeVM,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens};
+
+#[test]
+fn bytes_used_the_same_way_share_a_class() {
+    let dfa = TokensDFA::new(main_tokens());
+
+    assert!(dfa.class_count < 40, "{} classes", dfa.class_count);
+    assert_eq!(dfa.transitions.len(), dfa.states as usize * dfa.class_count);
+    // No token tells these letters apart, keywords do for the others.
+    assert_eq!(dfa.classes[b'b' as usize], dfa.classes[b'z' as usize]);
+    assert_ne!(dfa.classes[b'b' as usize], dfa.classes[b'i' as usize]);
+    assert_eq!(dfa.classes[b'0' as usize], dfa.classes[b'9' as usize]);
+    // Classes are numbered by their smallest byte.
+    assert_eq!(dfa.classes[0], 0);
+}
+
+#[test]
+fn every_byte_of_a_class_goes_to_the_same_state() {
+    let dfa = DFABuilder::new().construction(Construction::Derivatives).build(vec![
+        (0, TokenUses::Default, Regex::parse("[a-f][0-9a-f]*").unwrap()),
+        (1, TokenUses::Default, Regex::parse("[g-z][a-z]*").unwrap()),
+        (2, TokenUses::Default, Regex::parse("\\.\\.?").unwrap()),
+    ]);
+    assert_eq!(dfa.class_count, 5);
+    for state in 0..dfa.states as i32 {
+        for (char, class) in dfa.classes.iter().enumerate() {
+            assert_eq!(dfa.next(state, char as u8), dfa.next_by_class(state, *class as usize));
+        }
+    }
+}
+
+#[test]
+fn compressed_dfa_accepts_the_same() {
+    let vm = PikeVM::new(main_tokens());
+    for dfa in [TokensDFA::new(main_tokens()), TokensDFA::new(main_tokens()).minimize()] {
+        for input in all_strings(b"iwz9.'\\=</* \n\x80", 4) {
+            let input = String::from_utf8_lossy(&input).into_owned();
+            assert_eq!(dfa.test_string(input.clone()), vm.test_string(input.clone()), "on {:?}", input);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+
+mod generated {
+    include!("generated/main_lexer.rs");
+}
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    class::CharClass,
+    codegen::{CodegenError, LexerGenerator},
+    generate::RegexGenerator,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{main_tokens, Vars};
+
+const GENERATED: &str = "tests/generated/main_lexer.rs";
+
+/// The `main.rs` spec, plus identifiers followed by `(` declared first with two trailing contexts.
+fn spec() -> Vec<(Vars, TokenUses, Regex)> {
+    let mut tokens = main_tokens();
+    tokens.insert(0, (Vars::Id, TokenUses::GetLexeme, Regex::parse("[a-zA-Z][a-zA-Z0-9]*/\\(").unwrap()));
+    tokens.insert(1, (Vars::Id, TokenUses::GetLexeme, Regex::parse("[a-zA-Z][a-zA-Z0-9]*/ +\\(").unwrap()));
+    tokens
+}
+
+/// Run with `UPDATE_GENERATED=1` after changing the generator or the spec.
+#[test]
+fn generated_module_is_up_to_date() {
+    let source = LexerGenerator::new("crate::common::Vars").generate(spec()).unwrap();
+    if std::env::var_os("UPDATE_GENERATED").is_some() {
+        fs::write(GENERATED, &source).unwrap();
+    }
+    assert!(fs::read_to_string(GENERATED).unwrap() == source, "{} is stale, run with UPDATE_GENERATED=1", GENERATED);
+}
+
+type Lexed = Vec<(Vars, Option<String>)>;
+
+/// Tokens of the generated lexer, then those of `LexicalAnalysis`.
+fn lex_both(source: &str) -> (Lexed, Lexed) {
+    let path = std::env::temp_dir().join(format!("compiler_create_codegen_{}.txt", source.len()));
+    fs::write(&path, source).unwrap();
+    let expected = LexicalAnalysis::new(spec(), path.to_str().unwrap())
+        .map(|token| (token.t_type, token.t_name))
+        .collect();
+    fs::remove_file(&path).unwrap();
+    let generated = generated::Lexer::new(source.as_bytes())
+        .map(|token| (token.t_type, token.t_name))
+        .collect();
+    (generated, expected)
+}
+
+#[test]
+fn generated_lexer_matches_lexical_analysis() {
+    let source = "let x1 = 3.25; // if\nif (x1 >= 10) { print (x1, 'a', '\\n'); }\n/* while 1 */ while x1 != 0 { x1 = f(x1) - 1; }\n";
+    let (generated, expected) = lex_both(source);
+
+    assert_eq!(generated, expected);
+    assert!(generated.contains(&(Vars::Id, Some("print".to_string()))));
+    assert!(generated.contains(&(Vars::Id, Some("if".to_string()))));
+    // The commented out `while` is skipped.
+    assert_eq!(generated.iter().filter(|(name, _)| *name == Vars::While).count(), 1);
+}
+
+/// Random tokens with comments in between, the last token ending the input.
+#[test]
+fn generated_lexer_matches_on_random_sources() {
+    let tokens = spec();
+    let mut generator = RegexGenerator::new(24).alphabet(CharClass::from_range(b' '..=b'~'));
+    let mut comments = RegexGenerator::new(24).alphabet(CharClass::from_range(b'a'..=b'z').union(&CharClass::from_bytes(b" (*/;")));
+    let text = Regex::parse("[^*]*(\\*+[^*/][^*]*)*").unwrap();
+    let mut source = String::new();
+    for round in 0..400 {
+        let (_, mask, regex) = &tokens[(round * 7) % tokens.len()];
+        if matches!(mask, TokenUses::Default | TokenUses::GetLexeme) {
+            source.push_str(&String::from_utf8(generator.generate(regex).unwrap()).unwrap());
+            source.push(if round % 3 == 0 { '\n' } else { ' ' });
+        }
+        let comment = String::from_utf8(comments.generate(&text).unwrap()).unwrap();
+        match round % 11 {
+            0 => source.push_str(&format!("//{}\n", comment)),
+            5 => source.push_str(&format!("/*{}*/ ", comment)),
+            _ => {},
+        }
+    }
+    source.push_str("x1");
+
+    let (generated, expected) = lex_both(&source);
+    assert!(expected.len() > 200);
+    assert_eq!(expected.last(), Some(&(Vars::Id, Some("x1".to_string()))));
+    assert_eq!(generated, expected);
+}
+
+#[test]
+fn unclosed_comments_are_refused() {
+    let result = std::panic::catch_unwind(|| generated::Lexer::new(b"x /* y").count());
+    assert!(result.is_err());
+}
+
+#[test]
+fn named_groups_are_refused() {
+    let tokens = vec![(Vars::ConstFloat, TokenUses::GetLexeme, Regex::parse("(?<int>[0-9]+)\\.[0-9]+").unwrap())];
+    assert_eq!(
+        LexerGenerator::new("Vars").generate(tokens),
+        Err(CodegenError::Captures("ConstFloat".to_string()))
+    );
+}
@@ -0,0 +1,155 @@
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+
+use compiler_create::lexica::{regex::Regex, tokens::TokenUses};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Vars {
+    If, Else, While, Let, Set, Id,
+    ConstInt, ConstFloat, ConstChar,
+    Add, Sub, Mul, Div,
+    LessThan, GreatThan, LessEquals, GreatEquals, Equals, NotEquals,
+    RightBrace, LeftBrace, RightParentheses, LeftParentheses,
+    Comma, Semicolon,
+    Ws, LineComment, InitComment, EndComment,
+}
+
+/// The token specification of `src/main.rs`.
+pub fn main_tokens() -> Vec<(Vars, TokenUses, Regex)> {
+    let digits = Regex::plus(Regex::new(b'0'..=b'9'));
+
+    vec![
+        (Vars::If   , TokenUses::Default, Regex::new("if")),
+        (Vars::Else , TokenUses::Default, Regex::new("else")),
+        (Vars::While, TokenUses::Default, Regex::new("while")),
+        (Vars::Let  , TokenUses::Default, Regex::new("let")),
+        (Vars::Set  , TokenUses::Default, Regex::new("=")),
+
+        (Vars::Id, TokenUses::GetLexeme, Regex::parse("[a-zA-Z][a-zA-Z0-9]*").unwrap()),
+
+        (Vars::ConstInt    , TokenUses::GetLexeme  , digits.clone()),
+        (Vars::ConstFloat  , TokenUses::GetLexeme  , digits.clone()|Regex::new(b'.')|digits.clone()),
+        (Vars::ConstChar   , TokenUses::GetLexeme  , Regex::new(b'\'')|(Regex::not_in(b"\\'\n") + (Regex::new(b'\\')|Regex::any()))|Regex::new(b'\'')),
+
+        (Vars::Add, TokenUses::Default, Regex::new(b'+')),
+        (Vars::Sub, TokenUses::Default, Regex::new(b'-')),
+        (Vars::Mul, TokenUses::Default, Regex::new(b'*')),
+        (Vars::Div, TokenUses::Default, Regex::new(b'/')),
+
+        (Vars::LessThan     , TokenUses::Default, Regex::new(">")),
+        (Vars::GreatThan    , TokenUses::Default, Regex::new("<")),
+        (Vars::LessEquals   , TokenUses::Default, Regex::new(">=")),
+        (Vars::GreatEquals  , TokenUses::Default, Regex::new("<=")),
+        (Vars::Equals       , TokenUses::Default, Regex::new("==")),
+        (Vars::NotEquals    , TokenUses::Default, Regex::new("!=")),
+
+        (Vars::RightBrace      , TokenUses::Default, Regex::new(b'}')),
+        (Vars::LeftBrace       , TokenUses::Default, Regex::new(b'{')),
+        (Vars::RightParentheses, TokenUses::Default, Regex::new(b')')),
+        (Vars::LeftParentheses , TokenUses::Default, Regex::new(b'(')),
+
+        (Vars::Comma        , TokenUses::Default, Regex::new(b',')),
+        (Vars::Semicolon    , TokenUses::Default, Regex::new(b';')),
+
+        (Vars::Ws          , TokenUses::IgnoreThis        , Regex::new(vec![b'\r', b'\n', b'\t', b' ',  b'\0'])),
+        (Vars::LineComment , TokenUses::InitInlineComment , Regex::new("//")),
+        (Vars::InitComment , TokenUses::InitBlockComment  , Regex::new("/*")),
+        (Vars::EndComment  , TokenUses::EndBlockComment   , Regex::new("*/")),
+    ]
+}
+
+/// Reference matcher that walks the regex tree directly, independent of the automata.
+pub fn matches(regex: &Regex, input: &[u8]) -> bool {
+    ends(regex, input, 0).contains(&input.len())
+}
+
+fn ends(regex: &Regex, input: &[u8], start: usize) -> BTreeSet<usize> {
+    match regex {
+        Regex::Concat(regexs) => {
+            let mut current = BTreeSet::from([start]);
+            for re in regexs {
+                current = step(re, input, &current);
+            }
+            current
+        },
+        Regex::Union(regexs) => {
+            regexs.iter().flat_map(|re| ends(re, input, start)).collect()
+        },
+        Regex::Repeat(re) => repeat(re, input, BTreeSet::from([start]), None),
+        Regex::Bounded(_, min, Some(max)) if max < min => BTreeSet::new(),
+        Regex::Bounded(re, min, max) => {
+            let mut current = BTreeSet::from([start]);
+            for _ in 0..*min {
+                current = step(re, input, &current);
+            }
+            repeat(re, input, current, max.map(|max| max - min))
+        },
+        Regex::Intersection(regexs) => {
+            let mut sets = regexs.iter().map(|re| ends(re, input, start));
+            let first = sets.next().unwrap_or_else(|| (start..=input.len()).collect());
+            sets.fold(first, |acc, set| acc.intersection(&set).copied().collect())
+        },
+        Regex::Capture(_, re) => ends(re, input, start),
+        Regex::Trailing(head, tail) => step(tail, input, &ends(head, input, start)),
+        Regex::Complement(re) => {
+            let excluded = ends(re, input, start);
+            (start..=input.len()).filter(|end| !excluded.contains(end)).collect()
+        },
+        Regex::Word(word) => {
+            if input[start..].starts_with(word) {
+                return BTreeSet::from([start + word.len()]);
+            }
+            BTreeSet::new()
+        },
+        Regex::Char(c) => {
+            if input.get(start) == Some(c) {
+                return BTreeSet::from([start + 1]);
+            }
+            BTreeSet::new()
+        },
+        Regex::Class(class) => {
+            match input.get(start) {
+                Some(byte) if class.contains(*byte) => BTreeSet::from([start + 1]),
+                _ => BTreeSet::new(),
+            }
+        },
+        Regex::Empty => BTreeSet::from([start]),
+    }
+}
+
+fn step(regex: &Regex, input: &[u8], from: &BTreeSet<usize>) -> BTreeSet<usize> {
+    from.iter().flat_map(|position| ends(regex, input, *position)).collect()
+}
+
+fn repeat(regex: &Regex, input: &[u8], from: BTreeSet<usize>, limit: Option<u32>) -> BTreeSet<usize> {
+    let mut result = from.clone();
+    let mut frontier = from;
+    let mut iterations = 0;
+    while !frontier.is_empty() && limit.is_none_or(|limit| iterations < limit) {
+        let next: BTreeSet<usize> = step(regex, input, &frontier).difference(&result).copied().collect();
+        result.extend(next.iter().copied());
+        frontier = next;
+        iterations += 1;
+    }
+    result
+}
+
+/// Every string over `alphabet` with length up to `max_len`, shortest first.
+pub fn all_strings(alphabet: &[u8], max_len: usize) -> Vec<Vec<u8>> {
+    let mut strings = vec![Vec::new()];
+    let mut last = vec![Vec::new()];
+    for _ in 0..max_len {
+        let mut next = Vec::new();
+        for prefix in &last {
+            for byte in alphabet {
+                let mut string = prefix.clone();
+                string.push(*byte);
+                next.push(string);
+            }
+        }
+        strings.extend(next.iter().cloned());
+        last = next;
+    }
+    strings
+}
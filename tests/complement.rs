@@ -0,0 +1,87 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::{Construction, DFABuilder, TokensDFA, ERROR_STATE, INIT_STATE},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, matches};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind { Comment, Keyword, Id, Ws }
+
+fn both(tokens_regexs: Vec<(Kind, TokenUses, Regex)>) -> [TokensDFA<Kind>; 2] {
+    [Construction::Subset, Construction::Derivatives]
+        .map(|construction| DFABuilder::new().construction(construction).build(tokens_regexs.clone()))
+}
+
+#[test]
+fn intersection_excludes_keywords_from_identifiers() {
+    let id = Regex::parse("[a-z]+&~(if|do)").unwrap();
+    for dfa in both(vec![(Kind::Id, TokenUses::GetLexeme, id)]) {
+        for input in ["i", "d", "iff", "doo", "x", "ido"] {
+            assert_eq!(dfa.test_string(input.to_string()), Some(Kind::Id), "{}", input);
+        }
+        for input in ["if", "do", "", "a1"] {
+            assert_eq!(dfa.test_string(input.to_string()), None, "{}", input);
+        }
+    }
+}
+
+#[test]
+fn spliced_regexes_match_the_reference() {
+    for pattern in ["a~(b+)c", "(x&[a-z]*)y", "a(b*&~(bb))+c", "c(~(a|b)&[a-c]{2})", "b(~(ab*)|c)"] {
+        let regex = Regex::parse(pattern).unwrap();
+        for dfa in both(vec![(Kind::Id, TokenUses::Default, regex.clone())]) {
+            for input in all_strings(b"abcxy", 4) {
+                let lexed = dfa.test_string(String::from_utf8(input.clone()).unwrap());
+                assert_eq!(lexed.is_some(), matches(&regex, &input), "{} on {:?}", pattern, input);
+            }
+        }
+    }
+}
+
+/// Block comments as any bytes not containing `*/`, by complement.
+fn complement_comment_tokens() -> Vec<(Kind, TokenUses, Regex)> {
+    vec![
+        (Kind::Comment, TokenUses::IgnoreThis, Regex::parse("\\/\\*~([\\x00-\\xFF]*\\*\\/[\\x00-\\xFF]*)\\*\\/").unwrap()),
+        (Kind::Keyword, TokenUses::Default, Regex::new("if")),
+        (Kind::Id, TokenUses::GetLexeme, Regex::parse("[a-z]+").unwrap()),
+        (Kind::Ws, TokenUses::IgnoreThis, Regex::parse("[ \\n]").unwrap()),
+    ]
+}
+
+#[test]
+fn complement_comments_stop_once_closed() {
+    // Longer than the lexer's buffer, so it only passes if the comment does not swallow the rest.
+    let source = format!("/* a comment */ {}\n", "ab cd\n".repeat(120));
+    let path = std::env::temp_dir().join("compiler_create_complement.txt");
+    fs::write(&path, &source).unwrap();
+    for backend in [Backend::TokensDFA, Backend::PikeVM, Backend::CompressedDFA] {
+        let lexed: Vec<_> = LexicalAnalysis::with_backend(complement_comment_tokens(), path.to_str().unwrap(), backend)
+            .map(|token| (token.t_type, token.t_name))
+            .collect();
+        assert_eq!(lexed.len(), 240, "{:?}", backend);
+        assert!(lexed.iter().all(|(kind, _)| *kind == Kind::Id));
+    }
+    fs::remove_file(&path).unwrap();
+
+    for dfa in both(complement_comment_tokens()) {
+        let state = b"/* a */".iter().fold(INIT_STATE, |state, byte| dfa.next(state, *byte));
+        assert_eq!(dfa.finals[state as usize].0, Some(Kind::Comment));
+        assert_eq!(dfa.next(state, b' '), ERROR_STATE);
+    }
+}
+
+#[test]
+fn complement_of_everything_matches_nothing() {
+    let nothing = Regex::parse("~([\\x00-\\xFF]*)").unwrap();
+    let dfa = DFABuilder::new().construction(Construction::Derivatives).build(vec![(Kind::Id, TokenUses::Default, nothing)]);
+    assert_eq!(dfa.states, 1);
+    assert!((0..=255).all(|byte| dfa.next(INIT_STATE, byte) == ERROR_STATE));
+    assert_eq!(dfa.finals[INIT_STATE as usize].0, None);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::TokensDFA,
+    compressed::CompressedDFA,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens};
+
+#[test]
+fn packed_rows_give_the_same_transitions() {
+    let dfa = TokensDFA::new(main_tokens()).minimize();
+    let compressed = CompressedDFA::from_dfa(TokensDFA::new(main_tokens()).minimize());
+
+    for state in 0..dfa.states as i32 {
+        for byte in 0..=255 {
+            assert_eq!(compressed.next(state, byte), dfa.next(state, byte), "from {} over {}", state, byte);
+        }
+    }
+    for input in all_strings(b"iw1.'\\=</* \n", 4) {
+        let input = String::from_utf8(input).unwrap();
+        assert_eq!(compressed.test_string(input.clone()), dfa.test_string(input.clone()), "on {:?}", input);
+    }
+}
+
+#[test]
+fn report_shows_the_tables_shrinking() {
+    let compressed = CompressedDFA::new(main_tokens());
+    let report = compressed.report();
+
+    assert_eq!(report.states, compressed.states as usize);
+    assert!(report.packed_bytes < report.classes_bytes, "{}", report);
+    assert!(report.classes_bytes < report.full_bytes, "{}", report);
+    assert!(compressed.default.iter().any(|state| *state >= 0));
+    assert!(report.to_string().starts_with(&format!("{} states, {} byte classes: ", report.states, report.class_count)));
+}
+
+#[test]
+fn lexes_like_the_other_backends() {
+    let tokens = || {
+        let mut tokens = main_tokens();
+        for index in 0..50 {
+            tokens.insert(0, (common::Vars::Let, TokenUses::Default, Regex::new(format!("kw{}", index).as_str())));
+        }
+        tokens
+    };
+    let mut source = String::from("let x1 = 3.25;\nif x1 >= 10 { while x1 != 0 { x1 = x1 - 'a'; } }\n");
+    for index in (0..60).rev() {
+        source.push_str(&format!("kw{} kw{}x ", index, index));
+    }
+    source.push('\n');
+
+    let path = std::env::temp_dir().join("compiler_create_compressed.txt");
+    fs::write(&path, &source).unwrap();
+    let lex = |backend| LexicalAnalysis::with_backend(tokens(), path.to_str().unwrap(), backend)
+        .map(|token| (token.t_type, token.t_name))
+        .collect::<Vec<_>>();
+    let compressed = lex(Backend::CompressedDFA);
+    let dfa = lex(Backend::TokensDFA);
+    let vm = lex(Backend::PikeVM);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(compressed, dfa);
+    assert_eq!(compressed, vm);
+    assert_eq!(compressed.len(), 23 + 120);
+}
@@ -0,0 +1,101 @@
+use compiler_create::lexica::{
+    automata::TokensDFA,
+    definitions::{DefinitionError, RegexDefinitions},
+    parse::RegexParseErrorKind,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+fn definitions() -> RegexDefinitions {
+    let mut definitions = RegexDefinitions::new();
+    definitions.define("DIGIT", Regex::new(b'0'..=b'9'));
+    definitions.define_pattern("LETTER", "[a-zA-Z_]");
+    definitions.define_pattern("DIGITS", "{DIGIT}+");
+    definitions.define_pattern("FLOAT", "{DIGITS}\\.{DIGITS}");
+    definitions
+}
+
+#[test]
+fn references_are_substituted() {
+    let definitions = definitions();
+
+    assert_eq!(Regex::equivalent(&definitions.get("FLOAT").unwrap(), &Regex::parse("[0-9]+\\.[0-9]+").unwrap()), Ok(()));
+    assert_eq!(
+        Regex::equivalent(&definitions.parse("{LETTER}({LETTER}|{DIGIT})*").unwrap(), &Regex::parse("[a-zA-Z_][a-zA-Z_0-9]*").unwrap()),
+        Ok(())
+    );
+}
+
+#[test]
+fn references_can_be_repeated_with_bounds() {
+    let definitions = definitions();
+
+    assert_eq!(Regex::equivalent(&definitions.parse("{DIGIT}{2,3}").unwrap(), &Regex::parse("[0-9]{2,3}").unwrap()), Ok(()));
+    assert_eq!(definitions.parse("a{2}").unwrap(), Regex::parse("a{2}").unwrap());
+}
+
+#[test]
+fn undefined_names_are_reported() {
+    let mut definitions = definitions();
+    definitions.define_pattern("HEX", "0x{HEXDIGIT}+");
+
+    assert_eq!(definitions.parse("{NUMBER}"), Err(DefinitionError::Undefined("NUMBER".to_string())));
+    assert_eq!(definitions.get("HEX"), Err(DefinitionError::Undefined("HEXDIGIT".to_string())));
+    assert_eq!(Regex::parse("{DIGIT}").unwrap_err().kind, RegexParseErrorKind::UndefinedName("DIGIT".to_string()));
+}
+
+#[test]
+fn cycles_are_reported() {
+    let mut definitions = definitions();
+    definitions.define_pattern("A", "a{B}?");
+    definitions.define_pattern("B", "b{C}");
+    definitions.define_pattern("C", "{A}c");
+
+    let cycle = ["B", "C", "A", "B"].map(str::to_string).to_vec();
+    assert_eq!(definitions.get("B"), Err(DefinitionError::Cycle(cycle)));
+    assert_eq!(definitions.check(), Err(DefinitionError::Cycle(["A", "B", "C", "A"].map(str::to_string).to_vec())));
+}
+
+#[test]
+fn parse_errors_name_their_definition() {
+    let mut definitions = definitions();
+    definitions.define_pattern("BROKEN", "[a-");
+
+    match definitions.parse("{BROKEN}") {
+        Err(DefinitionError::Parse { name, error }) => {
+            assert_eq!(name.as_deref(), Some("BROKEN"));
+            assert_eq!(error.kind, RegexParseErrorKind::UnclosedClass);
+        },
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn trailing_contexts_stay_at_the_top() {
+    let mut definitions = definitions();
+    definitions.define_pattern("CALL", "[a-z]+/\\(");
+
+    assert!(matches!(definitions.get("CALL"), Ok(Regex::Trailing(..))));
+    for pattern in ["{CALL}", "x{CALL}", "({CALL})*", "{DIGITS}/{CALL}"] {
+        assert_eq!(definitions.parse(pattern), Err(DefinitionError::TrailingContext("CALL".to_string())), "{}", pattern);
+    }
+    assert_eq!(
+        DefinitionError::TrailingContext("CALL".to_string()).to_string(),
+        "definition 'CALL' has a trailing context and can't be referenced"
+    );
+}
+
+#[test]
+fn tokens_feed_the_dfa() {
+    let tokens_regexs = definitions().tokens(vec![
+        ("float", TokenUses::GetLexeme, "{FLOAT}"),
+        ("int", TokenUses::GetLexeme, "{DIGITS}"),
+        ("id", TokenUses::GetLexeme, "{LETTER}({LETTER}|{DIGIT})*"),
+    ]).unwrap();
+    let dfa = TokensDFA::new(tokens_regexs);
+
+    assert_eq!(dfa.test_string("3.14".to_string()), Some("float"));
+    assert_eq!(dfa.test_string("42".to_string()), Some("int"));
+    assert_eq!(dfa.test_string("x_1".to_string()), Some("id"));
+    assert_eq!(dfa.test_string("4.".to_string()), None);
+}
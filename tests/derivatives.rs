@@ -0,0 +1,58 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, TokensDFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens, Vars};
+
+fn both(tokens_regexs: Vec<(Vars, TokenUses, Regex)>) -> (TokensDFA<Vars>, TokensDFA<Vars>) {
+    let subset = DFABuilder::new().construction(Construction::Subset).build(tokens_regexs.clone());
+    let derivatives = DFABuilder::new().construction(Construction::Derivatives).build(tokens_regexs);
+    (subset, derivatives)
+}
+
+fn assert_same_tokens(subset: &TokensDFA<Vars>, derivatives: &TokensDFA<Vars>, input: &[u8]) {
+    let input = String::from_utf8_lossy(input).to_string();
+    assert_eq!(subset.test_string(input.clone()), derivatives.test_string(input.clone()), "on {:?}", input);
+}
+
+#[test]
+fn derivatives_match_subset_construction_on_main_tokens() {
+    let (subset, derivatives) = both(main_tokens());
+
+    for input in all_strings(b"ife1.'\\=</* \n", 4) {
+        assert_same_tokens(&subset, &derivatives, &input);
+    }
+    for input in ["while", "whilex", "let", "else", "x1y2", "3.1415", "'c'", "'\\''", "<=", "!=", "*/", "//", "\r"] {
+        assert_same_tokens(&subset, &derivatives, input.as_bytes());
+    }
+}
+
+#[test]
+fn derivatives_match_subset_construction_per_token() {
+    for (name, mask, regex) in main_tokens() {
+        let (subset, derivatives) = both(vec![(name, mask, regex)]);
+        for input in all_strings(b"ab1.'\\=/*", 3) {
+            assert_same_tokens(&subset, &derivatives, &input);
+        }
+    }
+}
+
+#[test]
+fn declaration_order_decides_priority() {
+    let (_, derivatives) = both(main_tokens());
+
+    assert_eq!(derivatives.test_string(String::from("if")), Some(Vars::If));
+    assert_eq!(derivatives.test_string(String::from("iff")), Some(Vars::Id));
+    assert_eq!(derivatives.test_string(String::from("42")), Some(Vars::ConstInt));
+}
+
+#[test]
+fn derivatives_build_no_more_states_than_subset() {
+    let (subset, derivatives) = both(main_tokens());
+
+    assert!(derivatives.states <= subset.states);
+}
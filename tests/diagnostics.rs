@@ -0,0 +1,73 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, INIT_STATE},
+    diagnostics::{spec_warnings, LexerSpecWarning},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{main_tokens, Vars};
+
+#[test]
+fn keywords_conflict_with_identifiers() {
+    for construction in [Construction::Subset, Construction::Derivatives] {
+        let (dfa, warnings) = DFABuilder::new().construction(construction).build_with_warnings(main_tokens());
+
+        let conflicts: Vec<_> = warnings.iter()
+            .map(|warning| match warning {
+                LexerSpecWarning::Conflict { state, example, tokens, winner } => (*state, example.clone(), tokens.clone(), *winner),
+                LexerSpecWarning::Shadowed { .. } => panic!("nothing is shadowed in the spec: {}", warning),
+            })
+            .collect();
+        assert_eq!(conflicts.len(), 4);
+        for (keyword, winner) in [("if", Vars::If), ("else", Vars::Else), ("while", Vars::While), ("let", Vars::Let)] {
+            let (state, _, tokens, _) = conflicts.iter().find(|(_, example, _, _)| example == keyword.as_bytes()).unwrap();
+            assert_eq!(tokens, &vec![winner, Vars::Id]);
+            assert_eq!(dfa.finals[*state as usize].0, Some(winner));
+            // The state is the one the DFA reaches on the example.
+            let reached = keyword.bytes().fold(INIT_STATE, |state, byte| dfa.next(state, byte));
+            assert_eq!(*state, reached, "{:?}", construction);
+        }
+    }
+    assert_eq!(
+        spec_warnings(&main_tokens())[0].to_string(),
+        "state 36 (reached by \"if\") accepts [If, Id], If wins as it is declared first"
+    );
+}
+
+#[test]
+fn shadowed_tokens_are_flagged() {
+    let tokens = vec![
+        ("id", TokenUses::GetLexeme, Regex::parse("[a-z]+").unwrap()),
+        ("if", TokenUses::Default, Regex::new("if")),
+        ("number", TokenUses::GetLexeme, Regex::parse("[0-9]+").unwrap()),
+        ("zero", TokenUses::Default, Regex::parse("0|00").unwrap()),
+        ("short", TokenUses::Default, Regex::parse("[0-9]|[a-z]").unwrap()),
+    ];
+    let shadowed: Vec<_> = spec_warnings(&tokens).into_iter()
+        .filter_map(|warning| match warning {
+            LexerSpecWarning::Shadowed { token, example, by } => Some((token, example, by)),
+            LexerSpecWarning::Conflict { .. } => None,
+        })
+        .collect();
+
+    assert_eq!(shadowed, vec![
+        ("if", b"if".to_vec(), vec!["id"]),
+        ("zero", b"0".to_vec(), vec!["number"]),
+        ("short", b"0".to_vec(), vec!["number", "id"]),
+    ]);
+}
+
+#[test]
+fn partial_overlaps_are_not_shadowing() {
+    let tokens = vec![
+        (0, TokenUses::Default, Regex::parse("[a-f]+").unwrap()),
+        (1, TokenUses::Default, Regex::parse("[a-z]+").unwrap()),
+        (2, TokenUses::Default, Regex::parse("[0-9]+").unwrap()),
+    ];
+    let warnings = spec_warnings(&tokens);
+
+    assert!(!warnings.is_empty());
+    assert!(warnings.iter().all(|warning| matches!(warning, LexerSpecWarning::Conflict { tokens, winner: 0, .. } if tokens == &vec![0, 1])));
+}
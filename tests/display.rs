@@ -0,0 +1,79 @@
+mod common;
+
+use compiler_create::lexica::{
+    class::CharClass,
+    regex::Regex,
+    unicode::UnicodeCategory
+};
+
+use common::{all_strings, matches};
+
+#[test]
+fn renders_conventional_notation() {
+    let letter = Regex::new(b'a'..=b'z') + Regex::new(b'A'..=b'Z') + Regex::new(b'_');
+    let id = letter.clone() | Regex::repeat(letter + Regex::new(b'0'..=b'9'));
+
+    assert_eq!(id.simplify().to_string(), "[A-Z_a-z][0-9A-Z_a-z]*");
+    assert_eq!(Regex::plus(Regex::new("ab")).to_string(), "(ab)+");
+    assert_eq!((Regex::new("if") + Regex::new("else")).to_string(), "if|else");
+    assert_eq!((Regex::new(b'a') | (Regex::new(b'b') + Regex::new(b'c'))).to_string(), "a(b|c)");
+    assert_eq!(Regex::times(Regex::new(b'x'), 2..=4).to_string(), "x{2,4}");
+    assert_eq!(Regex::at_least(Regex::new(b'x'), 2).to_string(), "x{2,}");
+    assert_eq!(Regex::optional(Regex::new("/*")).to_string(), "(\\/\\*)?");
+    assert_eq!(Regex::not_in(b"\n").to_string(), ".");
+    assert_eq!(Regex::not_in(b"'\\\n").to_string(), "[^\\n'\\\\]");
+    assert_eq!(Regex::from_class(CharClass::from_bytes(b"ab-")).to_string(), "[\\-ab]");
+}
+
+#[test]
+fn rendered_regexes_parse_back_to_the_same_language() {
+    let samples = vec![
+        Regex::parse("(a|b)*c?|a{2,3}b+").unwrap(),
+        Regex::parse("[^ab]|\\.|(\\(c\\))*").unwrap(),
+        Regex::repeat(Regex::repeat(Regex::new("ab"))) + Regex::Empty,
+        Regex::Concat(vec![Regex::Union(vec![Regex::new("ab")]), Regex::repeat(Regex::Concat(vec![Regex::Empty]))]),
+        Regex::plus(Regex::optional(Regex::new(b'c'))) | Regex::nothing(),
+        Regex::Union(vec![]) + Regex::new(b'a'),
+    ];
+    for regex in samples {
+        let text = regex.to_string();
+        let parsed = Regex::parse(&text).unwrap_or_else(|error| panic!("{} does not parse: {}", text, error));
+        for input in all_strings(b"abc.()", 4) {
+            assert_eq!(matches(&regex, &input), matches(&parsed, &input), "{} on {:?}", text, input);
+        }
+    }
+}
+
+#[test]
+fn only_top_level_trailing_contexts_render_with_a_slash() {
+    let (a, b) = (Regex::new(b'a'), Regex::new("bc"));
+    assert_eq!(Regex::followed_by(a.clone() + b.clone(), b.clone()).to_string(), "a|bc/bc");
+    assert_eq!(Regex::capture("x", Regex::followed_by(a.clone(), b.clone())).to_string(), "(?<x>abc)");
+    assert_eq!(Regex::followed_by(Regex::followed_by(a.clone(), b.clone()), a.clone()).to_string(), "abc/a");
+    assert_eq!(Regex::Bounded(Box::new(a.clone()), 3, Some(1)).to_string(), "[^\\x00-\\xFF]");
+
+    let samples = vec![
+        Regex::followed_by(Regex::plus(a.clone()), b.clone()),
+        Regex::capture("x", Regex::followed_by(a.clone(), b.clone())) | Regex::optional(a.clone()),
+        Regex::Union(vec![Regex::followed_by(a.clone(), Regex::followed_by(b.clone(), a.clone()))]),
+        Regex::repeat(Regex::followed_by(a.clone(), b.clone())) + Regex::Bounded(Box::new(b.clone()), 2, Some(0)),
+    ];
+    for regex in samples {
+        let text = regex.to_string();
+        let parsed = Regex::parse(&text).unwrap_or_else(|error| panic!("{} does not parse: {}", text, error));
+        assert_eq!(Regex::parse(&parsed.to_string()).unwrap(), parsed, "{}", text);
+        for input in all_strings(b"abc", 4) {
+            assert_eq!(matches(&regex, &input), matches(&parsed, &input), "{} on {:?}", text, input);
+        }
+    }
+}
+
+#[test]
+fn round_trips_unicode_and_escapes() {
+    for pattern in ["ação", "\\t\\r\\n\\0", "[\\x80-\\xFF]\\x7F", "x\\{1\\}"] {
+        let regex = Regex::parse(pattern).unwrap();
+        assert_eq!(Regex::parse(&regex.to_string()).unwrap(), regex);
+    }
+    let letter = Regex::unicode(UnicodeCategory::Letter);
+    assert_eq!(Regex::parse(&letter.to_string()).unwrap().simplify(), letter.simplify());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{TokensDFA, TokensNFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::main_tokens;
+
+#[test]
+fn dfa_labels_are_collapsed_ranges() {
+    let dot = TokensDFA::new(main_tokens()).to_dot();
+
+    assert!(dot.starts_with("digraph TokensDFA {"));
+    assert!(dot.contains("[label=\"0-9A-Za-z\"]"), "{}", dot);
+    assert!(dot.contains("shape=doublecircle"));
+    assert!(dot.contains("\\nId (GetLexeme)\"]"));
+    assert!(dot.contains("\\nWhile (Default)\"]"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn nfa_shows_epsilon_edges_and_every_final() {
+    let tokens = vec![
+        (0, TokenUses::Default, Regex::parse("(a|)b").unwrap()),
+        (1, TokenUses::GetLexeme, Regex::parse("ab").unwrap()),
+    ];
+    let dot = TokensNFA::new(tokens).to_dot();
+
+    assert!(dot.starts_with("digraph TokensNFA {"));
+    assert!(dot.contains("[label=\"ε\"]"));
+    assert!(dot.contains("\\n0 (Default)\"]"));
+    assert!(dot.contains("\\n1 (GetLexeme)\"]"));
+}
+
+#[test]
+fn prefix_limits_the_rendered_states() {
+    let dfa = TokensDFA::new(main_tokens());
+    let nodes = |dot: &str| dot.lines().filter(|line| !line.contains("->") && line.trim_start().starts_with(char::is_numeric)).count();
+
+    let whole = dfa.to_dot();
+    let after_quote = dfa.to_dot_from_prefix(b"'");
+    assert!(nodes(&after_quote) > 0);
+    assert!(nodes(&after_quote) < nodes(&whole));
+    assert!(!after_quote.contains("Id (GetLexeme)"));
+    assert!(after_quote.contains("ConstChar (GetLexeme)"));
+
+    assert_eq!(nodes(&dfa.to_dot_from_prefix(b"'ab")), 0);
+
+    let nfa = TokensNFA::new(main_tokens());
+    assert!(nodes(&nfa.to_dot_from_prefix(b"wh")) < nodes(&nfa.to_dot()));
+}
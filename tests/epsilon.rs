@@ -0,0 +1,72 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, TokensNFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, matches};
+
+/// Regexs that the old rewrite based epsilon moves got wrong: nullable repeats, empty
+/// alternatives and repeats starting on states that already have outgoing edges.
+const TRICKY: [&str; 16] = [
+    "(a*)*",
+    "(a|)b",
+    "(|a)*b",
+    "((ab)*c*)*",
+    "(a*b*)*c",
+    "a(b*)*c",
+    "(a*b|a*c)",
+    "((a|)*b)*",
+    "a*(ab)*",
+    "(a?b?)*a",
+    "(a|b*)(c|)",
+    "((a*)*|b)c",
+    "(ab|a)(ba|b)*",
+    "a{0,2}(b*a){1,2}",
+    "()*",
+    "(a|b|)*c?",
+];
+
+fn tokens(regex: &Regex) -> Vec<(u8, TokenUses, Regex)> {
+    vec![(0, TokenUses::Default, regex.clone())]
+}
+
+#[test]
+fn tricky_regexs_match_the_reference() {
+    for pattern in TRICKY {
+        let regex = Regex::parse(pattern).unwrap();
+        let subset = DFABuilder::new().construction(Construction::Subset).build(tokens(&regex));
+        let derivatives = DFABuilder::new().construction(Construction::Derivatives).build(tokens(&regex));
+
+        for input in all_strings(b"abc", 5) {
+            let expected = matches(&regex, &input).then_some(0);
+            let text = String::from_utf8(input).unwrap();
+
+            assert_eq!(TokensNFA::new(tokens(&regex)).test_string(text.clone()), expected, "NFA of {} on {:?}", pattern, text);
+            assert_eq!(subset.test_string(text.clone()), expected, "subset DFA of {} on {:?}", pattern, text);
+            assert_eq!(derivatives.test_string(text.clone()), expected, "derivatives DFA of {} on {:?}", pattern, text);
+        }
+    }
+}
+
+#[test]
+fn nullable_tokens_accept_the_empty_string() {
+    let dfa = DFABuilder::new().construction(Construction::Subset).build(vec![
+        (0, TokenUses::Default, Regex::parse("a+").unwrap()),
+        (1, TokenUses::Default, Regex::parse("b*").unwrap()),
+    ]);
+    assert_eq!(dfa.test_string(String::new()), Some(1));
+    assert_eq!(dfa.test_string("a".to_string()), Some(0));
+}
+
+#[test]
+fn epsilon_edges_are_explicit() {
+    let nfa = TokensNFA::new(tokens(&Regex::parse("(a|)b").unwrap()));
+    let closure = nfa.epsilon_closure(vec![0]);
+
+    assert!(closure.len() > 1);
+    assert!(nfa.transitions.values().flatten().all(|(_, state)| *state < nfa.states));
+    assert!(closure.iter().any(|state| nfa.next_states(*state, b'b').count() > 0));
+}
@@ -0,0 +1,56 @@
+use compiler_create::lexica::{
+    equivalence::Counterexample,
+    regex::Regex
+};
+
+#[test]
+fn refactored_number_token_is_equivalent() {
+    let digit = Regex::new(b'0'..=b'9');
+    let before = digit.clone() | Regex::repeat(digit.clone());
+    let after = Regex::parse("[0-9]+").unwrap();
+
+    assert_eq!(Regex::equivalent(&before, &after), Ok(()));
+    assert_eq!(Regex::equivalent(&after, &Regex::plus(digit)), Ok(()));
+}
+
+#[test]
+fn reports_a_shortest_counterexample() {
+    let identifier = Regex::parse("[a-z][a-z0-9]*").unwrap();
+    let changed = Regex::parse("[a-z]+[0-9]*").unwrap();
+
+    assert_eq!(
+        Regex::equivalent(&identifier, &changed),
+        Err(Counterexample { input: b"a0a".to_vec(), accepted_by_first: true })
+    );
+}
+
+#[test]
+fn nullable_regexs_differ_on_the_empty_string() {
+    let star = Regex::parse("a*").unwrap();
+    let plus = Regex::parse("a+").unwrap();
+
+    assert_eq!(
+        Regex::equivalent(&star, &plus),
+        Err(Counterexample { input: Vec::new(), accepted_by_first: true })
+    );
+}
+
+#[test]
+fn subset_is_one_directional() {
+    let keywords = Regex::new("if") + Regex::new("else");
+    let identifier = Regex::parse("[a-z]+").unwrap();
+
+    assert_eq!(Regex::is_subset(&keywords, &identifier), Ok(()));
+    assert_eq!(
+        Regex::is_subset(&identifier, &keywords),
+        Err(Counterexample { input: b"a".to_vec(), accepted_by_first: true })
+    );
+}
+
+#[test]
+fn complement_and_intersection_are_supported() {
+    let no_close = Regex::parse("~(.*\\*\\/.*)").unwrap();
+    let body = Regex::parse("([^*\\n]|\\*+[^*/\\n])*\\**").unwrap();
+
+    assert_eq!(Regex::equivalent(&(no_close & Regex::repeat(Regex::not_in(b"\n"))), &body), Ok(()));
+}
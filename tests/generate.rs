@@ -0,0 +1,113 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    class::CharClass,
+    generate::RegexGenerator,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{main_tokens, matches};
+
+fn printable() -> CharClass {
+    CharClass::from_ranges([b' '..=b'~', b'\t'..=b'\n'])
+}
+
+#[test]
+fn generated_strings_are_accepted() {
+    let samples = [
+        "[a-zA-Z_][a-zA-Z0-9_]*",
+        "[0-9]+(\\.[0-9]+)?([eE][+-]?[0-9]+)?",
+        "(ab|c){2,5}d?",
+        "'([^\\\\'\\n]|\\\\.)'",
+        "(?<int>[0-9]+)\\.(?<frac>[0-9]+)",
+        "[a-z]+&~(if|else|while)",
+        "\\/\\*~(.*\\*\\/.*)\\*\\/",
+        "x*/y",
+    ];
+    let mut generator = RegexGenerator::new(7);
+    for pattern in samples {
+        let regex = Regex::parse(pattern).unwrap();
+        for _ in 0..50 {
+            let input = generator.generate(&regex).unwrap();
+            assert!(matches(&regex, &input), "{:?} generated {:?}", pattern, String::from_utf8_lossy(&input));
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_strings() {
+    let regex = Regex::parse("[a-z]+[0-9]*").unwrap();
+    let run = |seed| {
+        let mut generator = RegexGenerator::new(seed);
+        (0..20).map(|_| generator.generate(&regex).unwrap()).collect::<Vec<_>>()
+    };
+    assert_eq!(run(42), run(42));
+    assert_ne!(run(42), run(43));
+}
+
+#[test]
+fn repeat_bound_and_alphabet_are_respected() {
+    let mut generator = RegexGenerator::new(1).max_repeat(3).alphabet(CharClass::from_range(b'a'..=b'c'));
+    let regex = Regex::parse("[a-z]*x?").unwrap();
+    for _ in 0..100 {
+        let input = generator.generate(&regex).unwrap();
+        assert!(input.len() <= 3);
+        assert!(input.iter().all(|byte| (b'a'..=b'c').contains(byte)));
+    }
+    assert_eq!(generator.generate(&Regex::parse("[a-c]+x").unwrap()), None);
+    assert_eq!(generator.generate(&Regex::nothing()), None);
+}
+
+#[test]
+fn shortest_examples() {
+    let shortest = |pattern: &str| Regex::parse(pattern).unwrap().shortest_example();
+
+    assert_eq!(shortest("[0-9]+\\.[0-9]+"), Some(b"0.0".to_vec()));
+    assert_eq!(shortest("while|if|else"), Some(b"if".to_vec()));
+    assert_eq!(shortest("a*"), Some(Vec::new()));
+    assert_eq!(shortest("[a-z]+&~(if|[a-h])"), Some(b"i".to_vec()));
+    assert_eq!(shortest("[a-z]+&[0-9]+"), None);
+}
+
+/// Every generated lexeme of the `main.rs` spec is lexed as a single token of its own type,
+/// unless an earlier declared token also matches it.
+#[test]
+fn lexer_classifies_generated_lexemes() {
+    let tokens = main_tokens();
+    let mut generator = RegexGenerator::new(2024).alphabet(printable());
+    let mut source = Vec::new();
+    let mut expected = Vec::new();
+
+    for (index, (_, mask, regex)) in tokens.iter().enumerate() {
+        if !matches!(mask, TokenUses::Default | TokenUses::GetLexeme) {
+            continue;
+        }
+        for _ in 0..20 {
+            let input = generator.generate(regex).unwrap();
+            let kind = tokens[..=index].iter()
+                .find(|(_, _, other)| matches(other, &input))
+                .map(|(kind, _, _)| *kind)
+                .unwrap();
+            expected.push((kind, String::from_utf8(input.clone()).unwrap()));
+            source.extend(input);
+            source.push(b'\n');
+        }
+    }
+
+    let path = std::env::temp_dir().join("compiler_create_generated.txt");
+    fs::write(&path, &source).unwrap();
+    let lexed: Vec<_> = LexicalAnalysis::new(main_tokens(), path.to_str().unwrap()).collect();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(lexed.len(), expected.len());
+    for (token, (kind, input)) in lexed.iter().zip(&expected) {
+        assert_eq!(token.t_type, *kind, "{:?}", input);
+        if let Some(lexeme) = &token.t_name {
+            assert_eq!(lexeme, input);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+// Generated by `compiler_create::lexica::codegen::LexerGenerator`, do not edit.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub t_type: crate::common::Vars,
+    pub t_name: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Uses {
+    Default,
+    GetLexeme,
+    IgnoreThis,
+    InitBlockComment,
+    EndBlockComment,
+    InitInlineComment,
+}
+
+const ERROR: i32 = -1;
+
+/// DFA rows indexed by byte class.
+struct Table {
+    class_count: usize,
+    classes: [u8; 256],
+    transitions: &'static [i32],
+    accepting: &'static [bool],
+}
+
+impl Table {
+    fn next(&self, state: i32, byte: u8) -> i32 {
+        self.transitions[state as usize * self.class_count + self.classes[byte as usize] as usize]
+    }
+
+    fn accepts(&self, input: &[u8]) -> bool {
+        let mut state = 0;
+        for byte in input {
+            state = self.next(state, *byte);
+            if state == ERROR {
+                return false;
+            }
+        }
+        self.accepting[state as usize]
+    }
+}
+
+static TOKENS: Table = Table {
+    class_count: 31,
+    classes: [
+        0, 1, 1, 1, 1, 1, 1, 1, 1, 0, 2, 1, 1, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        3, 4, 1, 1, 1, 1, 1, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 14, 14, 14, 14, 14, 14, 14, 14, 14, 1, 15, 16, 17, 18, 1,
+        1, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 19, 1, 20, 1, 1, 1,
+        1, 19, 19, 19, 19, 21, 22, 19, 23, 24, 19, 19, 25, 19, 19, 19, 19, 19, 19, 26, 27, 19, 19, 28, 19, 19, 19, 29, 1, 30, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    ],
+    transitions: &[
+        1, -1, 1, 1, 2, 3, 4, 5, 6, 7, 8, 9, -1, 10, 11, 12, 13, 14, 15, 16, -1, 17, 16, 16, 18, 19, 16, 16, 20, 21, 22,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 23, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        24, 24, -1, 24, 24, -1, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 25, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 26, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, 27, -1, -1, -1, -1, 28, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 29, -1, 11, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 30, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 31, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 32, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 35, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 36, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 37, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 38, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, 39, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24, 24,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 40, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, 33, -1, -1, 41, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 42, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 43, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 44, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, 40, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 45, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 46, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 47, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+        -1, -1, -1, 33, -1, -1, 34, -1, -1, -1, -1, -1, -1, -1, 16, -1, -1, -1, -1, 16, -1, 16, 16, 16, 16, 16, 16, 16, 16, -1, -1,
+    ],
+    accepting: &[
+        false, true, false, false, true, true, true, true, true, true, true, true, true, true, true, true,
+        true, true, true, true, true, true, true, true, false, false, true, true, true, false, true, true,
+        true, false, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    ],
+};
+
+static ACCEPTS: [Option<(crate::common::Vars, Uses, Option<usize>)>; 48] = [
+    None,
+    Some((crate::common::Vars::Ws, Uses::IgnoreThis, None)),
+    None,
+    None,
+    Some((crate::common::Vars::LeftParentheses, Uses::Default, None)),
+    Some((crate::common::Vars::RightParentheses, Uses::Default, None)),
+    Some((crate::common::Vars::Mul, Uses::Default, None)),
+    Some((crate::common::Vars::Add, Uses::Default, None)),
+    Some((crate::common::Vars::Comma, Uses::Default, None)),
+    Some((crate::common::Vars::Sub, Uses::Default, None)),
+    Some((crate::common::Vars::Div, Uses::Default, None)),
+    Some((crate::common::Vars::ConstInt, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Semicolon, Uses::Default, None)),
+    Some((crate::common::Vars::GreatThan, Uses::Default, None)),
+    Some((crate::common::Vars::Set, Uses::Default, None)),
+    Some((crate::common::Vars::LessThan, Uses::Default, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::LeftBrace, Uses::Default, None)),
+    Some((crate::common::Vars::RightBrace, Uses::Default, None)),
+    Some((crate::common::Vars::NotEquals, Uses::Default, None)),
+    None,
+    None,
+    Some((crate::common::Vars::EndComment, Uses::EndBlockComment, None)),
+    Some((crate::common::Vars::InitComment, Uses::InitBlockComment, None)),
+    Some((crate::common::Vars::LineComment, Uses::InitInlineComment, None)),
+    None,
+    Some((crate::common::Vars::GreatEquals, Uses::Default, None)),
+    Some((crate::common::Vars::Equals, Uses::Default, None)),
+    Some((crate::common::Vars::LessEquals, Uses::Default, None)),
+    None,
+    Some((crate::common::Vars::Id, Uses::GetLexeme, Some(0))),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::If, Uses::Default, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::ConstChar, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::ConstFloat, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, Some(1))),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Let, Uses::Default, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::Else, Uses::Default, None)),
+    Some((crate::common::Vars::Id, Uses::GetLexeme, None)),
+    Some((crate::common::Vars::While, Uses::Default, None)),
+];
+
+static END_COMMENT: Option<(Table, i32)> = Some((Table {
+    class_count: 3,
+    classes: [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ],
+    transitions: &[
+        -1, 1, -1,
+        -1, -1, 2,
+        -1, -1, -1,
+    ],
+    accepting: &[
+        false, false, true,
+    ],
+}, 2));
+
+static TRAILING: &[(usize, Table, Table)] = &[(0, Table {
+    class_count: 3,
+    classes: [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0,
+        0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 0, 0, 0, 0, 0,
+        0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ],
+    transitions: &[
+        -1, -1, 1,
+        -1, 1, 1,
+    ],
+    accepting: &[
+        false, true,
+    ],
+}, Table {
+    class_count: 2,
+    classes: [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ],
+    transitions: &[
+        -1, 1,
+        -1, -1,
+    ],
+    accepting: &[
+        false, true,
+    ],
+}), (1, Table {
+    class_count: 3,
+    classes: [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0,
+        0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 0, 0, 0, 0, 0,
+        0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ],
+    transitions: &[
+        -1, -1, 1,
+        -1, 1, 1,
+    ],
+    accepting: &[
+        false, true,
+    ],
+}, Table {
+    class_count: 3,
+    classes: [
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ],
+    transitions: &[
+        -1, 1, -1,
+        -1, 1, 2,
+        -1, -1, -1,
+    ],
+    accepting: &[
+        false, false, true,
+    ],
+})];
+
+pub struct Lexer<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Lexer { input, position: 0 }
+    }
+
+    /// The longest match wins, ties go to the first declared token.
+    pub fn next_token(&mut self) -> Option<Token> {
+        loop {
+            if self.position >= self.input.len() {
+                return None;
+            }
+            let start = self.position;
+            let mut state = 0;
+            let mut accepted = None;
+            for (index, byte) in self.input[start..].iter().enumerate() {
+                state = TOKENS.next(state, *byte);
+                if state == ERROR {
+                    break;
+                }
+                if let Some(token) = ACCEPTS[state as usize] {
+                    accepted = Some((token, index + 1));
+                }
+            }
+            let Some(((t_type, uses, rule), mut length)) = accepted else {
+                panic!("unexpected character {:?} at byte {}", self.input[start] as char, start);
+            };
+            if let Some((_, head, tail)) = TRAILING.iter().find(|(index, _, _)| Some(*index) == rule) {
+                let matched = &self.input[start..start + length];
+                if let Some(split) = (0..=length).rev().find(|split| head.accepts(&matched[..*split]) && tail.accepts(&matched[*split..])) {
+                    length = split;
+                }
+            }
+            self.position = start + length;
+
+            match uses {
+                Uses::IgnoreThis => {},
+                Uses::InitBlockComment => self.skip_block_comment(),
+                Uses::InitInlineComment => self.skip_inline_comment(),
+                Uses::EndBlockComment => panic!("comment not started at byte {}", start),
+                Uses::GetLexeme => {
+                    let t_name = String::from_utf8_lossy(&self.input[start..self.position]).into_owned();
+                    return Some(Token { t_type, t_name: Some(t_name) });
+                },
+                Uses::Default => return Some(Token { t_type, t_name: None }),
+            }
+        }
+    }
+
+    fn skip_inline_comment(&mut self) {
+        while self.position < self.input.len() && self.input[self.position] != b'\n' {
+            self.position += 1;
+        }
+    }
+
+    /// Restarts the end of comment automaton on every byte it rejects, that byte included.
+    fn skip_block_comment(&mut self) {
+        let Some((end, final_state)) = &END_COMMENT else {
+            panic!("a block comment was started but no token ends block comments");
+        };
+        loop {
+            let mut state = 0;
+            loop {
+                if state == *final_state {
+                    return;
+                }
+                let Some(byte) = self.input.get(self.position) else {
+                    panic!("comment not closed at byte {}", self.position);
+                };
+                state = end.next(state, *byte);
+                self.position += 1;
+                if state == ERROR {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
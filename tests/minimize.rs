@@ -0,0 +1,79 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::{Construction, DFABuilder, TokensDFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens, matches, Vars};
+
+#[test]
+fn spec_dfa_gets_smaller_and_accepts_the_same() {
+    let dfa = TokensDFA::new(main_tokens());
+    let minimized = TokensDFA::new(main_tokens()).minimize();
+
+    assert!(minimized.states < dfa.states, "{} -> {}", dfa.states, minimized.states);
+    for input in all_strings(b"iw1.'\\=</* \n", 4) {
+        let input = String::from_utf8(input).unwrap();
+        assert_eq!(minimized.test_string(input.clone()), dfa.test_string(input.clone()), "on {:?}", input);
+    }
+}
+
+#[test]
+fn tokens_and_uses_are_not_merged() {
+    let tokens = vec![
+        (0, TokenUses::Default, Regex::parse("a[0-9]").unwrap()),
+        (1, TokenUses::Default, Regex::parse("b[0-9]").unwrap()),
+        (0, TokenUses::IgnoreThis, Regex::parse("c[0-9]").unwrap()),
+        (0, TokenUses::Default, Regex::parse("d[0-9]").unwrap()),
+    ];
+    let dfa = DFABuilder::new().construction(Construction::Subset).build(tokens).minimize();
+
+    // The initial state, one state after each letter but `a` and `d`, and three accepting states.
+    assert_eq!(dfa.states, 1 + 3 + 3);
+    let end = |input: &[u8]| input.iter().fold(0, |state, char| dfa.next(state, *char));
+    assert_eq!(end(b"a1"), end(b"d7"));
+    assert_ne!(end(b"a1"), end(b"c1"));
+    assert_ne!(end(b"a1"), end(b"b1"));
+    assert_eq!(dfa.finals[end(b"c5") as usize], (Some(0), TokenUses::IgnoreThis));
+}
+
+#[test]
+fn minimal_whatever_the_construction() {
+    for pattern in ["(a|b)*abb", "(ab|a)(ba|b)*", "a*b*a*", "[a-z]+&~(ab|ba)"] {
+        let regex = Regex::parse(pattern).unwrap();
+        let tokens = || vec![(0, TokenUses::Default, regex.clone())];
+        let subset = DFABuilder::new().construction(Construction::Subset).build(tokens()).minimize();
+        let derivatives = DFABuilder::new().construction(Construction::Derivatives).build(tokens()).minimize();
+
+        assert_eq!(subset.states, derivatives.states, "{}", pattern);
+        for input in all_strings(b"abc", 5) {
+            let expected = matches(&regex, &input).then_some(0);
+            assert_eq!(subset.test_string(String::from_utf8(input).unwrap()), expected, "{}", pattern);
+        }
+    }
+    let nothing = TokensDFA::new(vec![(0, TokenUses::Default, Regex::parse("a&b").unwrap())]).minimize();
+    assert_eq!(nothing.states, 1);
+    assert_eq!(nothing.test_string("a".to_string()), None);
+}
+
+#[test]
+fn lexing_is_unchanged() {
+    let source = "let x1 = 3.25;\nif x1 >= 10 { while x1 != 0 { x1 = x1 - 'a'; } }\n";
+    let path = std::env::temp_dir().join("compiler_create_minimize.txt");
+    fs::write(&path, source).unwrap();
+    let lex = |backend| LexicalAnalysis::with_backend(main_tokens(), path.to_str().unwrap(), backend)
+        .map(|token| (token.t_type, token.t_name))
+        .collect::<Vec<_>>();
+    let minimized = lex(Backend::TokensDFA);
+    let reference = lex(Backend::PikeVM);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(minimized, reference);
+    assert_eq!(minimized.len(), 23);
+    assert!(minimized.contains(&(Vars::ConstChar, Some("'a'".to_string()))));
+}
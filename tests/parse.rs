@@ -0,0 +1,111 @@
+use compiler_create::lexica::{
+    automata::TokensDFA,
+    parse::{RegexParseError, RegexParseErrorKind},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+fn error(pattern: &str) -> RegexParseError {
+    match Regex::parse(pattern) {
+        Ok(_) => panic!("{} should not parse", pattern),
+        Err(error) => error,
+    }
+}
+
+fn accepts(pattern: &str, input: &str) -> bool {
+    let dfa = TokensDFA::new(vec![(0, TokenUses::Default, Regex::parse(pattern).unwrap())]);
+    dfa.test_string(input.to_string()) == Some(0)
+}
+
+#[test]
+fn parses_intersections_and_complements() {
+    let keywordless = Regex::parse("[a-z]+&~(if|do)").unwrap();
+    assert_eq!(keywordless, Regex::plus(Regex::new(b'a'..=b'z')) & !(Regex::new("if") + Regex::new("do")));
+    assert_eq!(keywordless.to_string(), "[a-z]+&~(if|do)");
+
+    assert_eq!(Regex::parse("a|b&c").unwrap(), Regex::new(b'a') + (Regex::new(b'b') & Regex::new(b'c')));
+    assert_eq!(Regex::parse("~~a*").unwrap(), !!Regex::repeat(Regex::new(b'a')));
+    assert_eq!(Regex::parse("~ab").unwrap().to_string(), "~ab");
+    assert_eq!(Regex::parse("(~a)b").unwrap(), Regex::Concat(vec![!Regex::new(b'a'), Regex::new(b'b')]));
+    assert_eq!(Regex::parse("~(ab)&c").unwrap().to_string(), "~(ab)&c");
+}
+
+#[test]
+fn intersections_need_both_operands() {
+    for (pattern, offset, kind) in [
+        ("&&", 0, RegexParseErrorKind::UnexpectedChar('&')),
+        ("&a", 0, RegexParseErrorKind::UnexpectedChar('&')),
+        ("a&", 2, RegexParseErrorKind::UnexpectedEnd),
+        ("a&&b", 2, RegexParseErrorKind::UnexpectedChar('&')),
+        ("(a&)", 3, RegexParseErrorKind::UnexpectedChar(')')),
+        ("a&|b", 2, RegexParseErrorKind::UnexpectedChar('|')),
+        ("~", 1, RegexParseErrorKind::UnexpectedEnd),
+    ] {
+        assert_eq!(error(pattern), RegexParseError { offset, kind }, "{}", pattern);
+    }
+    assert!(Regex::parse("\\&\\&").is_ok());
+    assert!(Regex::parse("()&a").is_ok());
+}
+
+#[test]
+fn malformed_escapes_point_at_the_backslash() {
+    for (pattern, offset, kind) in [
+        ("a\\x+f", 1, RegexParseErrorKind::InvalidEscape('x')),
+        ("\\x-1", 0, RegexParseErrorKind::InvalidEscape('x')),
+        ("ab\\xg0", 2, RegexParseErrorKind::InvalidEscape('x')),
+        ("\\x4", 0, RegexParseErrorKind::InvalidEscape('x')),
+        ("[a\\x 1]", 2, RegexParseErrorKind::InvalidEscape('x')),
+        ("\\q", 0, RegexParseErrorKind::InvalidEscape('q')),
+        ("ab\\", 3, RegexParseErrorKind::UnexpectedEnd),
+        ("\\p{Greek}", 0, RegexParseErrorKind::UnknownProperty("Greek".to_string())),
+        ("\\pL", 0, RegexParseErrorKind::InvalidEscape('p')),
+    ] {
+        assert_eq!(error(pattern), RegexParseError { offset, kind }, "{}", pattern);
+    }
+    assert!(accepts("\\x4F\\x4f", "OO"));
+}
+
+#[test]
+fn unbalanced_groups_and_classes() {
+    for (pattern, offset, kind) in [
+        ("(ab", 0, RegexParseErrorKind::UnclosedGroup),
+        ("a(b(c)", 1, RegexParseErrorKind::UnclosedGroup),
+        ("ab)", 2, RegexParseErrorKind::UnopenedGroup),
+        ("(a))", 3, RegexParseErrorKind::UnopenedGroup),
+        ("[ab", 0, RegexParseErrorKind::UnclosedClass),
+        ("x[a-", 1, RegexParseErrorKind::UnclosedClass),
+        ("[]", 0, RegexParseErrorKind::UnclosedClass),
+        ("[z-a]", 1, RegexParseErrorKind::InvalidRange('z', 'a')),
+        ("[a-\\d]", 1, RegexParseErrorKind::InvalidRange('a', '\\')),
+    ] {
+        assert_eq!(error(pattern), RegexParseError { offset, kind }, "{}", pattern);
+    }
+    // A `]` right after `[` or `[^` is a literal.
+    assert!(accepts("[]a]", "]") && accepts("[]a]", "a") && !accepts("[]a]", "b"));
+    assert!(accepts("[^]a]", "b") && !accepts("[^]a]", "]"));
+    assert!(accepts("a]", "a]"));
+}
+
+#[test]
+fn quantifiers_need_something_to_repeat() {
+    for (pattern, offset, kind) in [
+        ("*a", 0, RegexParseErrorKind::NothingToRepeat),
+        ("a|+", 2, RegexParseErrorKind::NothingToRepeat),
+        ("(*)", 1, RegexParseErrorKind::NothingToRepeat),
+        ("{2}", 0, RegexParseErrorKind::NothingToRepeat),
+        ("a{2", 1, RegexParseErrorKind::InvalidRepetition),
+        ("a{,2}", 1, RegexParseErrorKind::InvalidRepetition),
+        ("a{1,x}", 1, RegexParseErrorKind::InvalidRepetition),
+        ("ab{3,1}", 2, RegexParseErrorKind::InvalidRepetition),
+        ("a&?", 2, RegexParseErrorKind::NothingToRepeat),
+        ("a{1001}", 1, RegexParseErrorKind::RepetitionTooLarge),
+        ("a{2,5000000000}", 1, RegexParseErrorKind::InvalidRepetition),
+        ("(a{100}b){11}", 9, RegexParseErrorKind::RepetitionTooLarge),
+        ("[a-z]{1000}{2,}", 11, RegexParseErrorKind::RepetitionTooLarge),
+    ] {
+        assert_eq!(error(pattern), RegexParseError { offset, kind }, "{}", pattern);
+    }
+    assert!(Regex::parse("a**").is_ok());
+    assert!(Regex::parse("a{1000}").is_ok() && Regex::parse("(a{100}){10}").is_ok());
+    assert_eq!(error("*a").to_string(), "Error at byte 0: repetition operator without an expression");
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::TokensDFA,
+    class::CharClass,
+    generate::RegexGenerator,
+    pike::PikeVM,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens, Vars};
+
+fn lex<T>(tokens: Vec<(T, TokenUses, Regex)>, source: &str, backend: Backend) -> Vec<(T, Option<String>)>
+    where T: Eq + Copy + std::hash::Hash {
+    let path = std::env::temp_dir().join(format!("compiler_create_pike_{:?}_{}.txt", backend, source.len()));
+    fs::write(&path, source).unwrap();
+    let lexed = LexicalAnalysis::with_backend(tokens, path.to_str().unwrap(), backend)
+        .map(|token| (token.t_type, token.t_name))
+        .collect();
+    fs::remove_file(&path).unwrap();
+    lexed
+}
+
+#[test]
+fn pike_vm_agrees_with_the_dfa() {
+    let dfa = TokensDFA::new(main_tokens());
+    let vm = PikeVM::new(main_tokens());
+
+    for input in all_strings(b"ifel1.'\\=</* \n", 4) {
+        let input = String::from_utf8(input).unwrap();
+        assert_eq!(vm.test_string(input.clone()), dfa.test_string(input.clone()), "on {:?}", input);
+    }
+}
+
+#[test]
+fn maximal_munch_and_first_declared_wins() {
+    let source = "let x1 = 3.25;\nif x1 >= 10 { while x1 != 0 { x1 = x1 - 1; } }\n";
+    let expected = lex(main_tokens(), source, Backend::TokensDFA);
+
+    assert_eq!(lex(main_tokens(), source, Backend::PikeVM), expected);
+    assert_eq!(&expected[..5], &[
+        (Vars::Let, None),
+        (Vars::Id, Some("x1".to_string())),
+        (Vars::Set, None),
+        (Vars::ConstFloat, Some("3.25".to_string())),
+        (Vars::Semicolon, None),
+    ]);
+    assert!(expected.contains(&(Vars::LessEquals, None)));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Large {
+    Keyword(u16), Id, Number, Text, Ws,
+}
+
+/// Hundreds of keywords sharing prefixes with identifiers, plus a few literals.
+fn large_tokens() -> Vec<(Large, TokenUses, Regex)> {
+    let mut tokens = Vec::new();
+    for index in 0..300u16 {
+        let keyword = format!("{}{}", ["get", "set", "is", "has", "to"][index as usize % 5], index);
+        tokens.push((Large::Keyword(index), TokenUses::Default, Regex::new(keyword.as_str())));
+    }
+    tokens.push((Large::Id, TokenUses::GetLexeme, Regex::parse("[a-z][a-z0-9]*").unwrap()));
+    tokens.push((Large::Number, TokenUses::GetLexeme, Regex::parse("[0-9]+(\\.[0-9]+)?").unwrap()));
+    tokens.push((Large::Text, TokenUses::GetLexeme, Regex::parse("\"([^\"\\\\\\n]|\\\\.)*\"").unwrap()));
+    tokens.push((Large::Ws, TokenUses::IgnoreThis, Regex::parse("[ \\n]").unwrap()));
+    tokens
+}
+
+#[test]
+fn large_token_sets_lex_the_same() {
+    let tokens = large_tokens();
+    let mut generator = RegexGenerator::new(16).alphabet(CharClass::from_range(b' '..=b'~'));
+    let mut source = String::new();
+    for round in 0..300 {
+        let (_, _, regex) = &tokens[(round * 37) % (tokens.len() - 1)];
+        source.push_str(&String::from_utf8(generator.generate(regex).unwrap()).unwrap());
+        source.push(' ');
+    }
+    source.push('\n');
+
+    let expected = lex(large_tokens(), &source, Backend::TokensDFA);
+    assert_eq!(expected.len(), 300);
+    assert_eq!(lex(large_tokens(), &source, Backend::PikeVM), expected);
+}
@@ -0,0 +1,113 @@
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    automata::TokensDFA,
+    precompile::{precompile, SpecError, TokenSpec},
+    tokens::TokenUses
+};
+
+fn keywords() -> Vec<TokenSpec> {
+    vec![
+        TokenSpec::new("If", TokenUses::Default, "if"),
+        TokenSpec::new("Else", TokenUses::Default, "else"),
+        TokenSpec::new("Id", TokenUses::GetLexeme, "[a-z]+"),
+        TokenSpec::new("Ws", TokenUses::IgnoreThis, "[ \n]+"),
+    ]
+}
+
+#[test]
+fn conflicts_need_to_be_acknowledged() {
+    let errors = precompile(&keywords()).unwrap_err();
+    assert_eq!(errors, vec![
+        SpecError::Conflict { token: 0, losers: vec!["Id".to_string()], example: b"if".to_vec() },
+        SpecError::Conflict { token: 1, losers: vec!["Id".to_string()], example: b"else".to_vec() },
+    ]);
+    assert_eq!(
+        errors[0].to_string(),
+        "also accepted by Id (e.g. \"if\"), this token wins as it is declared first; write `over Id` if that is intended"
+    );
+
+    let mut specs = keywords();
+    specs[0] = specs[0].clone().over(&["Id"]);
+    specs[1] = specs[1].clone().over(&["Id"]);
+    let precompiled = precompile(&specs).unwrap();
+    assert_eq!(precompiled.names, vec!["If", "Else", "Id", "Ws"]);
+
+    let dfa = TokensDFA::from_bytes(&precompiled.dfa, &[0u32, 1, 2, 3]).unwrap();
+    assert_eq!(dfa.test_string("if".to_string()), Some(0));
+    assert_eq!(dfa.test_string("iff".to_string()), Some(2));
+}
+
+#[test]
+fn shadowed_tokens_and_bad_patterns_are_errors() {
+    let specs = vec![
+        TokenSpec::new("Id", TokenUses::GetLexeme, "[a-z]+"),
+        TokenSpec::new("If", TokenUses::Default, "if"),
+    ];
+    assert_eq!(precompile(&specs), Err(vec![
+        SpecError::Conflict { token: 0, losers: vec!["If".to_string()], example: b"if".to_vec() },
+        SpecError::Shadowed { token: 1, by: vec!["Id".to_string()], example: b"if".to_vec() },
+    ]));
+
+    let specs = vec![
+        TokenSpec::new("Int", TokenUses::GetLexeme, "[0-9]+"),
+        TokenSpec::new("Open", TokenUses::Default, "("),
+        TokenSpec::new("Close", TokenUses::Default, "[0-9"),
+    ];
+    let errors = precompile(&specs).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0], SpecError::Pattern { token: 1, .. }));
+    assert!(matches!(errors[1], SpecError::Pattern { token: 2, .. }));
+}
+
+#[test]
+fn over_must_name_a_declared_token() {
+    let specs = vec![
+        TokenSpec::new("If", TokenUses::Default, "if").over(&["Id", "Ident"]),
+        TokenSpec::new("Id", TokenUses::GetLexeme, "[a-z]+"),
+    ];
+    let errors = precompile(&specs).unwrap_err();
+    assert_eq!(errors, vec![SpecError::UnknownOver { token: 0, name: "Ident".to_string() }]);
+    assert_eq!(errors[0].to_string(), "`over Ident` names no token of the spec");
+}
+
+#[test]
+fn a_token_may_be_declared_more_than_once() {
+    let specs = vec![
+        TokenSpec::new("Num", TokenUses::GetLexeme, "[0-9]+"),
+        TokenSpec::new("Num", TokenUses::GetLexeme, "0x[0-9a-f]+"),
+        TokenSpec::new("Ws", TokenUses::IgnoreThis, " +"),
+    ];
+    let precompiled = precompile(&specs).unwrap();
+    assert_eq!(precompiled.names, vec!["Num", "Ws"]);
+    let dfa = TokensDFA::from_bytes(&precompiled.dfa, &[0u32, 1]).unwrap();
+    assert_eq!(dfa.test_string("0xff".to_string()), Some(0));
+}
+
+#[test]
+fn repeated_names_keep_their_own_trailing_contexts() {
+    let specs = vec![
+        TokenSpec::new("Call", TokenUses::GetLexeme, "[a-z]+/\\("),
+        TokenSpec::new("Call", TokenUses::GetLexeme, "[a-z]+/ +\\("),
+        TokenSpec::new("Id", TokenUses::GetLexeme, "[a-z]+"),
+        TokenSpec::new("Open", TokenUses::Default, "\\("),
+        TokenSpec::new("Close", TokenUses::Default, "\\)"),
+        TokenSpec::new("Ws", TokenUses::IgnoreThis, "[ \n]"),
+    ];
+    let precompiled = precompile(&specs).unwrap();
+    let dfa = TokensDFA::from_bytes(&precompiled.dfa, &[0u32, 1, 2, 3, 4]).unwrap();
+
+    let path = std::env::temp_dir().join("compiler_create_precompiled_calls.txt");
+    fs::write(&path, "f(x) g  (y)\n").unwrap();
+    let lexed: Vec<_> = LexicalAnalysis::from_dfa(dfa, path.to_str().unwrap())
+        .map(|token| (token.t_type, token.t_name))
+        .collect();
+    fs::remove_file(&path).unwrap();
+
+    let name = |text: &str| Some(text.to_string());
+    assert_eq!(lexed, vec![
+        (0, name("f")), (2, None), (1, name("x")), (3, None),
+        (0, name("g")), (2, None), (1, name("y")), (3, None),
+    ]);
+}
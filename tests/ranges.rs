@@ -0,0 +1,45 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, TokensNFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens, matches};
+
+#[test]
+fn classes_are_single_range_edges() {
+    let nfa = TokensNFA::new(vec![(0, TokenUses::Default, Regex::any())]);
+    assert_eq!(nfa.transitions.values().flatten().count(), 1);
+
+    let nfa = TokensNFA::new(vec![(0, TokenUses::Default, Regex::parse("[a-zA-Z_][a-zA-Z0-9_]*").unwrap())]);
+    assert_eq!(nfa.transitions.values().flatten().count(), 3 + 4);
+}
+
+#[test]
+fn overlapping_ranges_are_split() {
+    let tokens = vec![
+        (0, TokenUses::Default, Regex::parse("[a-m]x").unwrap()),
+        (1, TokenUses::Default, Regex::parse("[h-z]y").unwrap()),
+        (2, TokenUses::Default, Regex::parse("[^k]z").unwrap()),
+        (3, TokenUses::Default, Regex::parse("k[^x]").unwrap()),
+    ];
+    let dfa = DFABuilder::new().construction(Construction::Subset).build(tokens.clone());
+
+    for input in all_strings(b"agkmsxyz", 2) {
+        let expected = tokens.iter().find(|(_, _, regex)| matches(regex, &input)).map(|(name, _, _)| *name);
+        assert_eq!(dfa.test_string(String::from_utf8(input.clone()).unwrap()), expected, "on {:?}", input);
+    }
+}
+
+#[test]
+fn subset_agrees_with_derivatives_on_the_spec() {
+    let subset = DFABuilder::new().construction(Construction::Subset).build(main_tokens());
+    let derivatives = DFABuilder::new().construction(Construction::Derivatives).build(main_tokens());
+
+    for input in all_strings(b"iw1.'\\\"/*e \n", 4) {
+        let input = String::from_utf8(input).unwrap();
+        assert_eq!(subset.test_string(input.clone()), derivatives.test_string(input.clone()), "on {:?}", input);
+    }
+}
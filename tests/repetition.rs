@@ -0,0 +1,143 @@
+use compiler_create::lexica::{
+    automata::{Construction, DFABuilder, TokensDFA},
+    regex::Regex,
+    tokens::TokenUses
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Numbers {
+    ConstInt,
+    ConstFloat,
+    Exponent,
+}
+
+fn digit() -> Regex {
+    Regex::new(b'0'..=b'9')
+}
+
+fn single(regex: Regex) -> TokensDFA<u8> {
+    TokensDFA::new(vec![(0, TokenUses::Default, regex)])
+}
+
+#[test]
+fn plus_requires_at_least_one() {
+    let dfa = single(Regex::plus(digit()));
+
+    assert_eq!(dfa.test_string(String::from("")), None);
+    assert_eq!(dfa.test_string(String::from("7")), Some(0));
+    assert_eq!(dfa.test_string(String::from("2024")), Some(0));
+    assert_eq!(dfa.test_string(String::from("20a4")), None);
+}
+
+#[test]
+fn optional_accepts_zero_or_one() {
+    let dfa = single(Regex::new("a") | Regex::optional(Regex::new("b")) | Regex::new("c"));
+
+    assert_eq!(dfa.test_string(String::from("ac")), Some(0));
+    assert_eq!(dfa.test_string(String::from("abc")), Some(0));
+    assert_eq!(dfa.test_string(String::from("abbc")), None);
+}
+
+#[test]
+fn optional_at_the_start_of_a_token() {
+    let dfa = single(Regex::optional(Regex::new(b'-')) | Regex::plus(digit()));
+
+    assert_eq!(dfa.test_string(String::from("42")), Some(0));
+    assert_eq!(dfa.test_string(String::from("-42")), Some(0));
+    assert_eq!(dfa.test_string(String::from("--42")), None);
+}
+
+#[test]
+fn times_respects_both_bounds() {
+    let dfa = single(Regex::times(Regex::new("ab"), 2..=3));
+
+    assert_eq!(dfa.test_string(String::from("ab")), None);
+    assert_eq!(dfa.test_string(String::from("abab")), Some(0));
+    assert_eq!(dfa.test_string(String::from("ababab")), Some(0));
+    assert_eq!(dfa.test_string(String::from("abababab")), None);
+}
+
+#[test]
+fn times_accepts_a_single_count() {
+    let dfa = single(Regex::times(Regex::new(b'x'), 2..=2));
+
+    assert_eq!(dfa.test_string(String::from("x")), None);
+    assert_eq!(dfa.test_string(String::from("xx")), Some(0));
+    assert_eq!(dfa.test_string(String::from("xxx")), None);
+}
+
+#[test]
+#[should_panic(expected = "empty repetition range 3..=1")]
+fn times_rejects_empty_ranges() {
+    let (min, max) = (3, 1);
+    Regex::times(Regex::new(b'a'), min..=max);
+}
+
+#[test]
+#[should_panic(expected = "repetition 2..=500 above 1000 copies")]
+fn times_rejects_nested_counts_above_the_limit() {
+    Regex::times(Regex::times(Regex::new(b'a'), 3..=3), 2..=500);
+}
+
+#[test]
+#[should_panic(expected = "repetition 1001.. above 1000 copies")]
+fn at_least_rejects_counts_above_the_limit() {
+    Regex::at_least(Regex::new(b'a'), 1001);
+}
+
+/// Bounds built directly without `times` can be inverted, they match nothing.
+#[test]
+fn inverted_bounds_match_nothing() {
+    for construction in [Construction::Subset, Construction::Derivatives] {
+        for re in [Regex::new(b'a'), Regex::repeat(Regex::new(b'a'))] {
+            let inverted = Regex::Bounded(Box::new(re), 3, Some(1));
+            assert!(!inverted.is_nullable());
+            let dfa = DFABuilder::new()
+                .construction(construction)
+                .build(vec![(0, TokenUses::Default, inverted | Regex::new(b';'))]);
+            for input in [";", "a;", "aa;", "aaa;"] {
+                assert_eq!(dfa.test_string(String::from(input)), None, "{}", input);
+            }
+        }
+    }
+}
+
+#[test]
+fn at_least_is_open_ended() {
+    let dfa = single(Regex::at_least(Regex::new(b'x'), 3));
+
+    assert_eq!(dfa.test_string(String::from("xx")), None);
+    assert_eq!(dfa.test_string(String::from("xxx")), Some(0));
+    assert_eq!(dfa.test_string(String::from("xxxxxxxx")), Some(0));
+}
+
+#[test]
+fn parsed_operators_use_bounded_repetition() {
+    let dfa = single(Regex::parse("0x[0-9a-f]{1,4}u?").unwrap());
+
+    assert_eq!(dfa.test_string(String::from("0x")), None);
+    assert_eq!(dfa.test_string(String::from("0xff")), Some(0));
+    assert_eq!(dfa.test_string(String::from("0xbeefu")), Some(0));
+    assert_eq!(dfa.test_string(String::from("0x12345")), None);
+}
+
+#[test]
+fn number_tokens_without_duplicated_subexpressions() {
+    let digits = Regex::plus(digit());
+    let exponent = Regex::new(b'e') | Regex::optional(Regex::new(b'-')) | digits.clone();
+
+    let dfa = TokensDFA::new(vec![
+        (Numbers::ConstInt  , TokenUses::GetLexeme, digits.clone()),
+        (Numbers::ConstFloat, TokenUses::GetLexeme, digits.clone() | Regex::new(b'.') | digits.clone() | Regex::optional(exponent.clone())),
+        (Numbers::Exponent  , TokenUses::GetLexeme, exponent),
+    ]);
+
+    assert_eq!(dfa.test_string(String::from("0")), Some(Numbers::ConstInt));
+    assert_eq!(dfa.test_string(String::from("31415")), Some(Numbers::ConstInt));
+    assert_eq!(dfa.test_string(String::from("3.1415")), Some(Numbers::ConstFloat));
+    assert_eq!(dfa.test_string(String::from("6.02e23")), Some(Numbers::ConstFloat));
+    assert_eq!(dfa.test_string(String::from("1.6e-19")), Some(Numbers::ConstFloat));
+    assert_eq!(dfa.test_string(String::from("e-5")), Some(Numbers::Exponent));
+    assert_eq!(dfa.test_string(String::from("3.")), None);
+    assert_eq!(dfa.test_string(String::from(".5")), None);
+}
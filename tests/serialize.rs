@@ -0,0 +1,94 @@
+mod common;
+
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::LexicalAnalysis,
+    automata::{TokensDFA, INIT_STATE},
+    regex::Regex,
+    serialize::DFAFileError,
+    tokens::TokenUses
+};
+
+use common::{all_strings, main_tokens, Vars};
+
+/// The spec with named groups on floats, so lexeme rules are saved too. The group name of
+/// chars has no textual syntax, only the tree of the regex can be saved.
+fn tokens() -> Vec<(Vars, TokenUses, Regex)> {
+    main_tokens().into_iter()
+        .map(|(name, mask, regex)| match name {
+            Vars::ConstFloat => (name, mask, Regex::parse("(?<int>[0-9]+)\\.(?<frac>[0-9]+)").unwrap()),
+            Vars::ConstChar => (name, mask, Regex::capture("char body", regex)),
+            _ => (name, mask, regex),
+        })
+        .collect()
+}
+
+fn names() -> Vec<Vars> {
+    tokens().into_iter().map(|(name, _, _)| name).collect()
+}
+
+#[test]
+fn saved_dfa_loads_back_the_same() {
+    let dfa = TokensDFA::new(tokens()).minimize();
+    let path = std::env::temp_dir().join("compiler_create_saved.dfa");
+    let path = path.to_str().unwrap();
+    dfa.save(path, &names()).unwrap();
+    let loaded = TokensDFA::load(path, &names()).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded.states, dfa.states);
+    assert_eq!(loaded.finals, dfa.finals);
+    assert_eq!(loaded.classes, dfa.classes);
+    assert_eq!(loaded.transitions, dfa.transitions);
+    assert_eq!(loaded.rules, dfa.rules);
+    assert_eq!(loaded.lexeme_rules.regexs, dfa.lexeme_rules.regexs);
+    let float = b"3.25".iter().fold(INIT_STATE, |state, byte| dfa.next(state, *byte));
+    let rule = dfa.rules[float as usize];
+    assert!(rule.is_some());
+    assert_eq!(loaded.captures(rule, b"3.25"), dfa.captures(rule, b"3.25"));
+    for input in all_strings(b"iw1.'=/* \n", 3) {
+        let input = String::from_utf8(input).unwrap();
+        assert_eq!(loaded.test_string(input.clone()), dfa.test_string(input.clone()));
+    }
+}
+
+#[test]
+fn lexer_starts_from_a_loaded_dfa() {
+    let bytes = TokensDFA::new(tokens()).minimize().to_bytes(&names()).unwrap();
+    let source = "let x = 3.25; /* if 1 */ while x != 0 { x = x - 1; }\n";
+    let path = std::env::temp_dir().join("compiler_create_loaded_source.txt");
+    fs::write(&path, source).unwrap();
+    let lex = |lexer: LexicalAnalysis<Vars>| lexer.map(|token| (token.t_type, token.t_name, token.captures)).collect::<Vec<_>>();
+    let built = lex(LexicalAnalysis::new(tokens(), path.to_str().unwrap()));
+    let loaded = lex(LexicalAnalysis::from_dfa(TokensDFA::from_bytes(&bytes, &names()).unwrap(), path.to_str().unwrap()));
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, built);
+    assert!(!loaded.iter().any(|(name, _, _)| *name == Vars::If));
+    assert_eq!(loaded[3].2, vec![("int".to_string(), 0..1), ("frac".to_string(), 2..4)]);
+}
+
+#[test]
+fn damaged_or_stale_data_is_rejected() {
+    let bytes = TokensDFA::new(tokens()).to_bytes(&names()).unwrap();
+    let load = |bytes: &[u8]| TokensDFA::from_bytes(bytes, &names()).map(|_| ());
+
+    assert!(load(&bytes).is_ok());
+    let mut flipped = bytes.clone();
+    flipped[bytes.len() / 2] ^= 1;
+    assert!(matches!(load(&flipped), Err(DFAFileError::Checksum)));
+    assert!(matches!(load(&bytes[..bytes.len() - 1]), Err(DFAFileError::Checksum)));
+    assert!(matches!(load(&bytes[..12]), Err(DFAFileError::Checksum)));
+    assert!(matches!(load(b""), Err(DFAFileError::NotADFA)));
+    assert!(matches!(load(b"digraph TokensDFA {}"), Err(DFAFileError::NotADFA)));
+
+    let mut newer = bytes.clone();
+    newer[6] += 1;
+    assert!(matches!(load(&newer), Err(DFAFileError::UnsupportedVersion(2))));
+
+    let fewer = &names()[1..];
+    assert!(matches!(TokensDFA::from_bytes(&bytes, fewer), Err(DFAFileError::TokenCount { saved: 29, given: 28 })));
+    assert!(matches!(TokensDFA::new(tokens()).to_bytes(fewer), Err(DFAFileError::UnknownToken)));
+    assert!(matches!(TokensDFA::<Vars>::load("/nonexistent/compiler_create.dfa", &names()), Err(DFAFileError::Io(_))));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use compiler_create::lexica::{
+    automata::TokensNFA,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+use common::{all_strings, matches};
+
+fn assert_same_language(regex: Regex) {
+    let simplified = regex.clone().simplify();
+    for input in all_strings(b"abc", 6) {
+        assert_eq!(
+            matches(&regex, &input),
+            matches(&simplified, &input),
+            "simplified regex disagrees on {:?}", String::from_utf8_lossy(&input)
+        );
+    }
+}
+
+fn nfa_states(regex: Regex) -> i32 {
+    TokensNFA::new(vec![(0, TokenUses::Default, regex)]).states
+}
+
+fn samples() -> Vec<Regex> {
+    let a = || Regex::new(b'a');
+    let b = || Regex::new(b'b');
+    let c = || Regex::new(b'c');
+    vec![
+        (a() | b()) | (c() | a()),
+        ((a() + b()) + (c() + a())) + b(),
+        Regex::repeat(Regex::repeat(a())),
+        Regex::repeat(Regex::optional(a() | b())),
+        Regex::repeat(a() + Regex::Empty),
+        Regex::plus(Regex::plus(a())),
+        Regex::optional(Regex::optional(c())),
+        Regex::optional(Regex::plus(a())),
+        Regex::plus(Regex::optional(b())),
+        Regex::times(Regex::repeat(a()), 2..=3),
+        Regex::times(Regex::plus(a()), 0..=2),
+        a() | Regex::repeat(a()),
+        Regex::repeat(b()) | b(),
+        Regex::repeat(a()) | Regex::repeat(a()) | c(),
+        Regex::new("ab") + Regex::new("ab") + Regex::Empty,
+        Regex::Concat(vec![Regex::Empty, Regex::Concat(vec![a(), Regex::Empty]), Regex::Word(vec![])]),
+        a() | Regex::nothing() | b(),
+        Regex::repeat(Regex::nothing()),
+        Regex::new(b'a'..=b'b') + Regex::new(b'c') + Regex::new("bc"),
+        Regex::parse("(a|b)(a|b)*|c?|(ab|ab)+").unwrap(),
+        Regex::parse("a{0,1}{0,1}b{2,}c{1,2}").unwrap(),
+        Regex::parse("((a*)*|(b|)*)c").unwrap(),
+    ]
+}
+
+#[test]
+fn simplified_regexes_accept_the_same_language() {
+    for regex in samples() {
+        assert_same_language(regex);
+    }
+}
+
+#[test]
+fn simplify_is_idempotent() {
+    for regex in samples() {
+        let once = regex.simplify();
+        let twice = once.clone().simplify();
+        assert!(once == twice);
+    }
+}
+
+#[test]
+fn flattens_nested_concat_into_a_word() {
+    let regex = (Regex::new(b'a') | Regex::new(b'b')) | (Regex::new("cd") | Regex::Empty);
+    assert!(regex.simplify() == Regex::Word(b"abcd".to_vec()));
+}
+
+#[test]
+fn merges_single_byte_alternatives_into_a_class() {
+    let regex = Regex::new(b'a') + Regex::new(b'b') + (Regex::new(b'x') + Regex::new(b'a'));
+    assert!(regex.simplify() == Regex::one_of(b"abx"));
+}
+
+#[test]
+fn removes_duplicate_alternatives() {
+    let regex = Regex::new("if") + Regex::new("else") + Regex::new("if");
+    assert!(regex.simplify() == Regex::Union(vec![Regex::new("if"), Regex::new("else")]));
+}
+
+#[test]
+fn collapses_idempotent_repeats() {
+    let a = Regex::new(b'a');
+    assert!(Regex::repeat(Regex::repeat(a.clone())).simplify() == Regex::repeat(a.clone()));
+    assert!(Regex::plus(Regex::plus(a.clone())).simplify() == Regex::plus(a.clone()));
+    assert!(Regex::repeat(Regex::plus(a.clone())).simplify() == Regex::repeat(a.clone()));
+    assert!((a.clone() | Regex::repeat(a.clone())).simplify() == Regex::plus(a));
+}
+
+#[test]
+fn simplification_reduces_nfa_states() {
+    let letters = Regex::new(b'a') + Regex::new(b'b') + Regex::new(b'c') + Regex::new(b'd') + Regex::new(b'e');
+    let regex = Regex::repeat(Regex::repeat(letters.clone() + letters)) | Regex::new(b'x') | Regex::new(b'y');
+
+    assert!(nfa_states(regex.clone().simplify()) < nfa_states(regex));
+}
@@ -0,0 +1,153 @@
+use std::fs;
+
+use compiler_create::lexica::{
+    analysis::{Backend, LexicalAnalysis},
+    automata::{Construction, DFABuilder, TokensDFA, INIT_STATE},
+    parse::RegexParseErrorKind,
+    regex::Regex,
+    tokens::TokenUses
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Function, Id, Int, Float, Range, LeftParentheses, RightParentheses, Ws,
+}
+
+fn tokens() -> Vec<(Kind, TokenUses, Regex)> {
+    vec![
+        (Kind::Function, TokenUses::GetLexeme, Regex::parse("[a-z]+/ *\\(").unwrap()),
+        (Kind::Id, TokenUses::GetLexeme, Regex::parse("[a-z]+").unwrap()),
+        (Kind::Int, TokenUses::GetLexeme, Regex::parse("[0-9]+/\\.\\.").unwrap()),
+        (Kind::Int, TokenUses::GetLexeme, Regex::parse("[0-9]+").unwrap()),
+        (Kind::Float, TokenUses::GetLexeme, Regex::parse("[0-9]+\\.[0-9]*").unwrap()),
+        (Kind::Range, TokenUses::Default, Regex::new("..")),
+        (Kind::LeftParentheses, TokenUses::Default, Regex::new(b'(')),
+        (Kind::RightParentheses, TokenUses::Default, Regex::new(b')')),
+        (Kind::Ws, TokenUses::IgnoreThis, Regex::new(vec![b' ', b'\n'])),
+    ]
+}
+
+fn lex(name: &str, source: &str) -> Vec<(Kind, Option<String>)> {
+    let path = std::env::temp_dir().join(format!("compiler_create_trailing_{}.txt", name));
+    fs::write(&path, source).unwrap();
+    let tokens = LexicalAnalysis::new(tokens(), path.to_str().unwrap())
+        .map(|token| (token.t_type, token.t_name))
+        .collect();
+    fs::remove_file(&path).unwrap();
+    tokens
+}
+
+/// Lexeme length of `matched` under the rules of the state the whole match ends in.
+fn lexeme_length(dfa: &TokensDFA<Kind>, matched: &[u8]) -> usize {
+    let state = matched.iter().fold(INIT_STATE, |state, byte| dfa.next(state, *byte));
+    dfa.lexeme_length(dfa.rules[state as usize], matched)
+}
+
+fn lexeme(kind: Kind, text: &str) -> (Kind, Option<String>) {
+    (kind, Some(text.to_string()))
+}
+
+#[test]
+fn parses_and_displays_trailing_context() {
+    let regex = Regex::parse("[0-9]+/\\.\\.").unwrap();
+    assert_eq!(regex, Regex::followed_by(Regex::plus(Regex::new(b'0'..=b'9')), Regex::new("..")));
+    assert_eq!(regex.to_string(), "[0-9]+/\\.\\.");
+    assert_eq!(Regex::parse("a\\/b").unwrap(), Regex::new("a/b"));
+
+    assert_eq!(Regex::parse("(a/b)").unwrap_err().kind, RegexParseErrorKind::UnexpectedChar('/'));
+    assert_eq!(Regex::parse("a/b/c").unwrap_err().kind, RegexParseErrorKind::UnexpectedChar('/'));
+}
+
+#[test]
+fn lexeme_length_excludes_the_context() {
+    for construction in [Construction::Subset, Construction::Derivatives] {
+        let dfa: TokensDFA<Kind> = DFABuilder::new().construction(construction).build(tokens());
+
+        assert_eq!(dfa.test_string("foo (".to_string()), Some(Kind::Function));
+        assert_eq!(lexeme_length(&dfa, b"foo ("), 3);
+        assert_eq!(lexeme_length(&dfa, b"12.."), 2);
+        assert_eq!(lexeme_length(&dfa, b"1.5"), 3);
+    }
+}
+
+#[test]
+fn lexer_consumes_only_the_head() {
+    assert_eq!(lex("range", "3..5 3.5 3.\n"), vec![
+        lexeme(Kind::Int, "3"),
+        (Kind::Range, None),
+        lexeme(Kind::Int, "5"),
+        lexeme(Kind::Float, "3.5"),
+        lexeme(Kind::Float, "3."),
+    ]);
+    assert_eq!(lex("call", "print (x) y\n"), vec![
+        lexeme(Kind::Function, "print"),
+        (Kind::LeftParentheses, None),
+        lexeme(Kind::Id, "x"),
+        (Kind::RightParentheses, None),
+        lexeme(Kind::Id, "y"),
+    ]);
+}
+
+#[test]
+fn each_declaration_keeps_its_own_context() {
+    let tokens = || vec![
+        (Kind::Function, TokenUses::GetLexeme, Regex::parse("[a-z]+/\\(").unwrap()),
+        (Kind::Function, TokenUses::GetLexeme, Regex::parse("[a-z]+/ *\\(").unwrap()),
+        (Kind::Id, TokenUses::GetLexeme, Regex::parse("[a-z]+").unwrap()),
+        (Kind::LeftParentheses, TokenUses::Default, Regex::new(b'(')),
+        (Kind::RightParentheses, TokenUses::Default, Regex::new(b')')),
+        (Kind::Ws, TokenUses::IgnoreThis, Regex::new(vec![b' ', b'\n'])),
+    ];
+    for construction in [Construction::Subset, Construction::Derivatives] {
+        let dfa: TokensDFA<Kind> = DFABuilder::new().construction(construction).build(tokens());
+        assert_eq!(lexeme_length(&dfa, b"foo("), 3);
+        assert_eq!(lexeme_length(&dfa, b"foo ("), 3);
+    }
+
+    let source = "f(x) g  (y)\n";
+    let path = std::env::temp_dir().join("compiler_create_trailing_declarations.txt");
+    fs::write(&path, source).unwrap();
+    for backend in [Backend::TokensDFA, Backend::PikeVM, Backend::CompressedDFA] {
+        let lexed: Vec<_> = LexicalAnalysis::with_backend(tokens(), path.to_str().unwrap(), backend)
+            .map(|token| (token.t_type, token.t_name))
+            .collect();
+        assert_eq!(lexed, vec![
+            lexeme(Kind::Function, "f"),
+            (Kind::LeftParentheses, None),
+            lexeme(Kind::Id, "x"),
+            (Kind::RightParentheses, None),
+            lexeme(Kind::Function, "g"),
+            (Kind::LeftParentheses, None),
+            lexeme(Kind::Id, "y"),
+            (Kind::RightParentheses, None),
+        ], "{:?}", backend);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn context_starting_inside_a_character() {
+    let tokens = vec![
+        (Kind::Id, TokenUses::GetLexeme, Regex::parse("[\\x00-\\xFF]/[\\x80-\\xBF]").unwrap()),
+        (Kind::Int, TokenUses::GetLexeme, Regex::parse("[\\x80-\\xBF]").unwrap()),
+    ];
+    let path = std::env::temp_dir().join("compiler_create_trailing_split.txt");
+    fs::write(&path, "é").unwrap();
+    for backend in [Backend::TokensDFA, Backend::PikeVM, Backend::CompressedDFA] {
+        let lexed: Vec<_> = LexicalAnalysis::with_backend(tokens.clone(), path.to_str().unwrap(), backend)
+            .map(|token| (token.t_type, token.t_name))
+            .collect();
+        assert_eq!(lexed, vec![lexeme(Kind::Id, "\u{FFFD}"), lexeme(Kind::Int, "\u{FFFD}")], "{:?}", backend);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "declaration 1 has a trailing context below its top level")]
+fn nested_contexts_are_rejected() {
+    let call = Regex::followed_by(Regex::new("f"), Regex::new(b'('));
+    TokensDFA::new(vec![
+        (Kind::Id, TokenUses::GetLexeme, Regex::new("g")),
+        (Kind::Function, TokenUses::GetLexeme, Regex::capture("name", call)),
+    ]);
+}
@@ -0,0 +1,145 @@
+use compiler_create::lexica::{
+    automata::{TokensDFA, ERROR_STATE, INIT_STATE},
+    parse::RegexParseErrorKind,
+    regex::Regex,
+    tokens::TokenUses,
+    unicode::UnicodeCategory
+};
+
+fn dfa(regex: Regex) -> TokensDFA<()> {
+    TokensDFA::new(vec![((), TokenUses::Default, regex)])
+}
+
+fn accepts(dfa: &TokensDFA<()>, input: &[u8]) -> bool {
+    let mut state = INIT_STATE;
+    for byte in input {
+        state = dfa.next(state, *byte);
+        if state == ERROR_STATE {
+            return false;
+        }
+    }
+    dfa.finals[state as usize].0.is_some()
+}
+
+fn utf8(c: char) -> Vec<u8> {
+    c.to_string().into_bytes()
+}
+
+#[test]
+fn hex_escapes_are_always_bytes() {
+    let byte = dfa(Regex::parse("[\\xE9]").unwrap());
+    assert!(accepts(&byte, &[0xE9]));
+    assert!(!accepts(&byte, &utf8('é')));
+    assert!(accepts(&dfa(Regex::parse("\\xE9").unwrap()), &[0xE9]));
+    let bytes = dfa(Regex::parse("[\\xE9\\x41]").unwrap());
+    assert!(accepts(&bytes, &[0xE9]) && accepts(&bytes, b"A") && !accepts(&bytes, &utf8('é')));
+
+    let code_point = dfa(Regex::parse("[\\u{E9}ç]").unwrap());
+    assert!(accepts(&code_point, &utf8('é')));
+    assert!(accepts(&code_point, &utf8('ç')));
+    assert!(!accepts(&code_point, &[0xE9]));
+    assert!(accepts(&dfa(Regex::parse("\\u{E9}").unwrap()), &utf8('é')));
+    let ascii = dfa(Regex::parse("[\\u{41}-\\u{43}]").unwrap());
+    assert!(accepts(&ascii, b"B") && !accepts(&ascii, b"D"));
+
+    for (pattern, offset) in [("[\\xE9ç]", 1), ("[ç\\xE9]", 3), ("[\\x80-é]", 1), ("[a\\xFF\\p{L}]", 2), ("[\\u{E9}\\xE9]", 7)] {
+        let error = Regex::parse(pattern).err().unwrap();
+        assert_eq!((error.offset, error.kind), (offset, RegexParseErrorKind::MixedBytesAndCodePoints), "{}", pattern);
+    }
+    // Ascii bytes are the same either way.
+    assert!(Regex::parse("[\\x41ç]").is_ok());
+
+    for pattern in ["\\u", "\\u{}", "\\u{D800}", "\\u{110000}", "\\u{1234567}", "\\u{12", "\\u{g}"] {
+        assert_eq!(Regex::parse(pattern).err().unwrap().kind, RegexParseErrorKind::InvalidEscape('u'), "{}", pattern);
+    }
+}
+
+#[test]
+fn code_point_ranges_split_at_encoding_boundaries() {
+    let ranges = [
+        (0x7F, 0x80), (0x7FF, 0x800), (0xFFFF, 0x10000), (0x7E, 0x81), (0x7FE, 0x801),
+        (0xD7FF, 0xE000), (0xD000, 0xF000), (0x10FFFF, 0x10FFFF), (0x10000, 0x10FFFF), (0xFFF, 0x1000),
+    ];
+    for (start, end) in ranges {
+        let matcher = dfa(Regex::from_code_points(&[(start, end)]));
+        let window = (start.saturating_sub(0x100)..=(end + 0x100).min(char::MAX as u32))
+            .chain([0, 0x7F, 0x80, 0x7FF, 0x800, 0xFFFF, 0x10000, 0x10FFFF]);
+        for code_point in window {
+            let Some(c) = char::from_u32(code_point) else { continue };
+            assert_eq!(accepts(&matcher, &utf8(c)), (start..=end).contains(&code_point), "U+{:X} in {:X}-{:X}", code_point, start, end);
+        }
+    }
+}
+
+#[test]
+fn every_scalar_value_and_nothing_else_is_encoded() {
+    let any = dfa(Regex::from_code_points(&[(0, char::MAX as u32)]));
+    for c in (0..=char::MAX as u32).filter_map(char::from_u32) {
+        assert!(accepts(&any, &utf8(c)), "U+{:X}", c as u32);
+    }
+    let invalid: [&[u8]; 8] = [
+        &[0x80], &[0xC0, 0x80], &[0xC1, 0xBF], &[0xE0, 0x9F, 0xBF],
+        &[0xED, 0xA0, 0x80], &[0xED, 0xBF, 0xBF], &[0xF4, 0x90, 0x80, 0x80], &[0xF5, 0x80, 0x80, 0x80],
+    ];
+    for bytes in invalid {
+        assert!(!accepts(&any, bytes), "{:X?}", bytes);
+    }
+
+    // Surrogates are skipped even when asked for.
+    let surrogates = dfa(Regex::from_code_points(&[(0xD800, 0xDFFF)]));
+    assert!(!accepts(&surrogates, &[0xED, 0xA0, 0x80]));
+    assert!(!accepts(&surrogates, &utf8('\u{D7FF}')));
+}
+
+#[test]
+fn category_tables_hold_known_code_points() {
+    for category in [UnicodeCategory::Letter, UnicodeCategory::Digit, UnicodeCategory::XidStart, UnicodeCategory::XidContinue] {
+        let ranges = category.ranges();
+        assert!(ranges.iter().all(|(low, high)| low <= high && *high <= char::MAX as u32), "{:?}", category);
+        assert!(ranges.windows(2).all(|pair| pair[0].1 + 1 < pair[1].0), "{:?} is not sorted and disjoint", category);
+    }
+
+    let letter = UnicodeCategory::Letter;
+    for c in ['a', 'Z', 'ª', 'é', 'ǅ', 'ж', 'א', 'ع', 'क', '中', 'ㄱ', '한', '𐐀', '𠀀'] {
+        assert!(letter.contains(c), "{:?}", c);
+    }
+    for c in ['0', '_', ' ', '×', '÷', '٣', '\u{300}', '€', '\u{10FFFF}'] {
+        assert!(!letter.contains(c), "{:?}", c);
+    }
+
+    let digit = UnicodeCategory::Digit;
+    for c in ['0', '9', '٣', '۵', '७', '๑', '０', '𝟎'] {
+        assert!(digit.contains(c), "{:?}", c);
+    }
+    for c in ['a', '²', '½', 'Ⅻ', '/', ':'] {
+        assert!(!digit.contains(c), "{:?}", c);
+    }
+
+    assert!(UnicodeCategory::XidStart.contains('a') && UnicodeCategory::XidStart.contains('中'));
+    assert!(!UnicodeCategory::XidStart.contains('_') && !UnicodeCategory::XidStart.contains('1'));
+    for c in ['_', '1', '\u{300}', '٣', 'a'] {
+        assert!(UnicodeCategory::XidContinue.contains(c), "{:?}", c);
+    }
+    assert!(!UnicodeCategory::XidContinue.contains('-') && !UnicodeCategory::XidContinue.contains(' '));
+}
+
+/// The tables are from an older Unicode than the standard library, so only inclusions are checked.
+#[test]
+fn category_tables_agree_with_the_standard_library() {
+    let code_points = |category: UnicodeCategory| category.ranges().iter()
+        .flat_map(|(low, high)| *low..=*high)
+        .filter_map(char::from_u32)
+        .collect::<Vec<_>>();
+    assert!(code_points(UnicodeCategory::Letter).iter().all(|c| c.is_alphabetic()));
+    assert!(code_points(UnicodeCategory::Digit).iter().all(|c| c.is_numeric()));
+    assert!(code_points(UnicodeCategory::XidStart).iter().all(|c| UnicodeCategory::XidContinue.contains(*c)));
+    // Only the letters XID_Continue drops for NFKC closure are missing from it.
+    let missing: Vec<u32> = code_points(UnicodeCategory::Letter).into_iter()
+        .filter(|c| !UnicodeCategory::XidContinue.contains(*c))
+        .map(|c| c as u32)
+        .collect();
+    assert_eq!(missing, [
+        0x37A, 0x2E2F, 0xFC5E, 0xFC5F, 0xFC60, 0xFC61, 0xFC62, 0xFC63, 0xFDFA,
+        0xFDFB, 0xFE70, 0xFE72, 0xFE74, 0xFE76, 0xFE78, 0xFE7A, 0xFE7C, 0xFE7E,
+    ]);
+}